@@ -1,7 +1,7 @@
 use std::path::Path;
 use std::fs::File;
 
-use object::{Object, ObjectSymbol};
+use object::{Object, ObjectSegment, ObjectSymbol};
 use cpp_demangle::{Symbol, DemangleOptions};
 use memmap::Mmap;
 
@@ -19,3 +19,15 @@ pub fn iter<P: AsRef<Path>>(path: P) -> Vec<(String, usize)> {
             .map(|name| (name, sym.address() as usize)))
         .collect()
 }
+
+/// Returns the on-disk bytes of the given virtual address range, as they would be mapped
+/// by the loader, or `None` if the range isn't backed by file data of a single segment.
+/// Also `None` if the file can't be read or parsed.
+pub fn bytes_at<P: AsRef<Path>>(path: P, address: usize, len: usize) -> Option<Vec<u8>> {
+    let file = File::open(path).ok()?;
+    let file = unsafe { Mmap::map(&file) }.ok()?;
+    let file = object::File::parse(&*file).ok()?;
+    file.segments()
+        .find_map(|segment| segment.data_range(address as u64, len as u64).ok().flatten())
+        .map(|data| data.to_vec())
+}
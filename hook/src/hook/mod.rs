@@ -1,5 +1,24 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 mod raw;
 mod typed;
 
 pub use raw::RawHook;
-pub use typed::TypedHook;
\ No newline at end of file
+pub use typed::TypedHook;
+
+static ACTIVE_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of calls currently executing any interceptor.
+///
+/// The interceptors count themselves from their first instruction up to their final `ret`.
+/// Once all hooks are disabled and this is 0, threads only have the `ret` or the `jmp` into the
+/// interceptor left to execute on hook pages, so give them a short grace period before
+/// removing the hooks.
+pub fn active_calls() -> usize {
+    ACTIVE_CALLS.load(Ordering::SeqCst)
+}
+
+/// Address of the counter behind `active_calls`, which the interceptors increment and decrement
+pub(crate) fn active_calls_addr() -> usize {
+    ACTIVE_CALLS.as_ptr() as usize
+}
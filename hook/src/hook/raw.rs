@@ -1,8 +1,8 @@
-use std::{mem, slice};
+use std::{mem, ptr, slice};
 use std::ffi::c_void;
 use crate::{ArgsRef, get_orig_bytes, IsaAbi, trampoline};
 use crate::function_decoder::FunctionDecoder;
-use crate::hook_memory_page::HookMemoryPageBuilder;
+use crate::hook_memory_page::{self, HookMemoryPageBuilder};
 use crate::isa_abi::Array;
 
 #[repr(C)]
@@ -90,6 +90,19 @@ impl<IA: IsaAbi, T> RawHook<IA, T> {
     pub fn context(&self) -> &T {
         &self.user_context
     }
+    /// Disable the hook and free all memory associated with it.
+    ///
+    /// # Safety
+    /// * no thread may currently execute the hook function, the interceptor or the trampoline,
+    ///   nor may any thread start doing so afterwards
+    /// * the hook must not be used after this call
+    pub unsafe fn remove(&'static self) {
+        self.disable();
+        // the page is read-only, so move the context out instead of dropping it in-place
+        drop(unsafe { ptr::read(&self.user_context) });
+        // the hook struct lives at the start of its memory page
+        unsafe { hook_memory_page::free_page(self as *const Self as usize) };
+    }
 }
 
 /// SAFETY: implementation must be correct for the OS
@@ -44,6 +44,13 @@ impl<IA: IsaAbi, F: RawFnWithoutHook<IA, T>, T: 'static> TypedHook<IA, F, T> {
     pub fn context(&self) -> &T {
         &self.hook.context().user_context
     }
+    /// Disable the hook and free all memory associated with it.
+    ///
+    /// # Safety
+    /// See [`RawHook::remove`].
+    pub unsafe fn remove(&'static self) {
+        unsafe { self.hook.remove() }
+    }
     pub unsafe fn call_original_function(&self, args: F::Args) -> usize {
         let mut a = IA::Args::new();
         let mut a_ref = ArgsRef::<IA>::new(&mut a);
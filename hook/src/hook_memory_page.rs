@@ -6,6 +6,23 @@ use crate::{assemble, CallTrampoline, RawHook, Interceptor};
 use crate::isa_abi::IsaAbi;
 use crate::trampoline::Trampoline;
 
+/// size of the memory page containing the hook struct, trampoline, interceptor and call-trampoline
+const HOOK_MEMORY_PAGE_SIZE: usize = 8192;
+
+/// Unmap a hook memory page previously leaked by [`HookMemoryPageBuilderFinished::finalize`].
+///
+/// # Safety
+/// * `page_addr` must be the address returned by `finalize`
+/// * no code on the page may be executing or be executed afterwards
+pub unsafe fn free_page(page_addr: usize) {
+    #[cfg(windows)] {
+        unsafe { winapi::um::memoryapi::UnmapViewOfFile(page_addr as *const std::ffi::c_void); }
+    }
+    #[cfg(unix)] {
+        unsafe { libc::munmap(page_addr as *mut std::ffi::c_void, HOOK_MEMORY_PAGE_SIZE); }
+    }
+}
+
 #[must_use]
 pub struct HookMemoryPageBuilder<IA: IsaAbi, T> {
     map: MmapMut,
@@ -14,7 +31,7 @@ pub struct HookMemoryPageBuilder<IA: IsaAbi, T> {
 impl<IA: IsaAbi, T: 'static> HookMemoryPageBuilder<IA, T> {
     pub fn new() -> Self {
         Self {
-            map: MmapMut::map_anon(8192).unwrap(),
+            map: MmapMut::map_anon(HOOK_MEMORY_PAGE_SIZE).unwrap(),
             _marker: PhantomData,
         }
     }
@@ -1,5 +1,5 @@
 use std::mem::offset_of;
-use iced_x86::code_asm::{AsmRegister32, CodeAssembler, eax, ebp, ebx, ecx, edi, edx, esi, esp, ptr, xmm0, xmm1, xmm2, xmm3, xmm4, xmm5, xmm6, xmm7};
+use iced_x86::code_asm::{AsmRegister32, CodeAssembler, dword_ptr, eax, ebp, ebx, ecx, edi, edx, esi, esp, ptr, xmm0, xmm1, xmm2, xmm3, xmm4, xmm5, xmm6, xmm7};
 use iced_x86::IcedError;
 use crate::args::{Args, ArgsLoadContext, ArgsStoreContext};
 use crate::{ArgsRef, assemble, CallTrampoline, Interceptor, IsaAbi, RawHook};
@@ -119,6 +119,10 @@ unsafe impl IsaAbi for I686_MSVC_Thiscall {
             unreachable!("I686_MSVC_Thiscall is only supported on 32-bit targets")
         }
 
+        // count the call until the `ret` below, see `active_calls`;
+        // eax was already clobbered by the jmp to the interceptor
+        a.mov(eax, crate::hook::active_calls_addr() as u32).unwrap();
+        a.lock().inc(dword_ptr(eax)).unwrap();
         // function prologue with frame pointer
         a.push(ebp).unwrap();
         a.mov(ebp, esp).unwrap();
@@ -163,6 +167,9 @@ unsafe impl IsaAbi for I686_MSVC_Thiscall {
         a.pop(eax).unwrap();
         // function epilogue
         a.pop(ebp).unwrap();
+        // ecx is caller-saved and doesn't hold the return value
+        a.mov(ecx, crate::hook::active_calls_addr() as u32).unwrap();
+        a.lock().dec(dword_ptr(ecx)).unwrap();
         if stack_arg_size == 0 {
             a.ret().unwrap();
         } else {
@@ -1,5 +1,5 @@
 use std::mem::offset_of;
-use iced_x86::code_asm::{AsmRegister64, CodeAssembler, ptr, r10, r11, r12, r13, r14, r15, r8, r9, rax, rbp, rbx, rcx, rdi, rdx, rsi, rsp, xmm0, xmm1, xmm2, xmm3, xmm4, xmm5, xmm6, xmm7};
+use iced_x86::code_asm::{AsmRegister64, CodeAssembler, ptr, qword_ptr, r10, r11, r12, r13, r14, r15, r8, r9, rax, rbp, rbx, rcx, rdi, rdx, rsi, rsp, xmm0, xmm1, xmm2, xmm3, xmm4, xmm5, xmm6, xmm7};
 use iced_x86::IcedError;
 use crate::args::{Args, ArgsLoadContext, ArgsStoreContext};
 use crate::{ArgsRef, assemble, CallTrampoline, Interceptor, IsaAbi, RawHook};
@@ -89,6 +89,10 @@ unsafe impl IsaAbi for X86_64_SystemV {
             unreachable!("X86_64_SystemV is only supported on 64-bit targets")
         }
 
+        // count the call until the `ret` below, see `active_calls`;
+        // rax was already clobbered by the jmp to the interceptor
+        a.mov(rax, crate::hook::active_calls_addr() as u64).unwrap();
+        a.lock().inc(qword_ptr(rax)).unwrap();
         // function prologue with frame pointer
         a.push(rbp).unwrap();
         a.mov(rbp, rsp).unwrap();
@@ -138,6 +142,9 @@ unsafe impl IsaAbi for X86_64_SystemV {
         a.pop(rbx).unwrap();
         // function epilogue
         a.pop(rbp).unwrap();
+        // r11 is caller-saved and doesn't hold the return value
+        a.mov(r11, crate::hook::active_calls_addr() as u64).unwrap();
+        a.lock().dec(qword_ptr(r11)).unwrap();
         if stack_arg_size == 0 {
             a.ret().unwrap();
         } else {
@@ -11,7 +11,7 @@ mod hook_memory_page;
 
 pub use args::{ArgsRef, ArgsBoxed};
 pub use isa_abi::{IsaAbi, X86_64_SystemV, I686_MSVC_Thiscall};
-pub use hook::{RawHook, TypedHook};
+pub use hook::{RawHook, TypedHook, active_calls};
 
 // # Design Overview
 //
@@ -0,0 +1,340 @@
+//! Per-element differences between maps and three-way merging of maps.
+//!
+//! Properties are compared on their JSON representation, such that nested properties like the
+//! spawn point are reported with dotted paths like `spawn.location.x`.
+
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use serde::Serialize;
+use serde_json::{json, Value};
+use crate::{Cluster, Element, ElementIndex, ElementType, MapError, RefunctMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeLocation {
+    /// metadata, spawn point and world settings
+    Map,
+    /// initial z and rise speed
+    Cluster(usize),
+    Element(ElementIndex),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertyChange {
+    pub property: String,
+    pub old: String,
+    pub new: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified(Vec<PropertyChange>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapChange {
+    pub location: ChangeLocation,
+    pub kind: ChangeKind,
+}
+
+/// Property changed differently in both maps of a merge, the merged map keeps "our" value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub location: ChangeLocation,
+    pub property: String,
+    pub base: String,
+    pub ours: String,
+    pub theirs: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    pub map: RefunctMap,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl Display for ChangeLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeLocation::Map => write!(f, "map"),
+            ChangeLocation::Cluster(cluster_index) => write!(f, "cluster {cluster_index}"),
+            ChangeLocation::Element(index) => write!(f, "{index}"),
+        }
+    }
+}
+
+impl Display for MapChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ChangeKind::Added => write!(f, "+ {}", self.location),
+            ChangeKind::Removed => write!(f, "- {}", self.location),
+            ChangeKind::Modified(changes) => {
+                write!(f, "~ {}", self.location)?;
+                for PropertyChange { property, old, new } in changes {
+                    write!(f, "\n    {property}: {old} -> {new}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for Conflict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "! {} {}: base {}, ours {}, theirs {}", self.location, self.property, self.base, self.ours, self.theirs)
+    }
+}
+
+/// Everything of the map except for the clusters, triggers are compared as a whole
+fn map_properties(map: &RefunctMap) -> Value {
+    json!({ "metadata": map.metadata, "spawn": map.spawn, "world": map.world, "triggers": map.triggers })
+}
+fn cluster_properties(cluster: &Cluster) -> Value {
+    json!({ "z": cluster.z, "rise_speed": cluster.rise_speed })
+}
+fn to_value(t: &impl Serialize) -> Value {
+    serde_json::to_value(t).unwrap()
+}
+fn display(value: Option<&Value>) -> String {
+    match value {
+        None => "(missing)".to_string(),
+        Some(value) => value.to_string(),
+    }
+}
+fn join(path: &str, key: &str) -> String {
+    match path {
+        "" => key.to_string(),
+        path => format!("{path}.{key}"),
+    }
+}
+
+/// All changed leaf properties from `old` to `new`
+fn diff_value(path: &str, old: Option<&Value>, new: Option<&Value>, changes: &mut Vec<PropertyChange>) {
+    match (old, new) {
+        (Some(Value::Object(old)), Some(Value::Object(new))) => {
+            let keys: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+            for key in keys {
+                diff_value(&join(path, key), old.get(key), new.get(key), changes);
+            }
+        }
+        (old, new) if old != new => changes.push(PropertyChange { property: path.to_string(), old: display(old), new: display(new) }),
+        _ => (),
+    }
+}
+
+/// Change of a map, cluster or element which exists in at least one of the maps
+fn compare(location: ChangeLocation, old: Option<Value>, new: Option<Value>) -> Option<MapChange> {
+    let kind = match (old, new) {
+        (Some(old), Some(new)) => {
+            let mut changes = Vec::new();
+            diff_value("", Some(&old), Some(&new), &mut changes);
+            if changes.is_empty() {
+                return None;
+            }
+            ChangeKind::Modified(changes)
+        }
+        (Some(_), None) => ChangeKind::Removed,
+        (None, Some(_)) => ChangeKind::Added,
+        (None, None) => return None,
+    };
+    Some(MapChange { location, kind })
+}
+
+/// Compare two maps, returning all changed map properties, clusters and elements.
+pub fn diff(old: &RefunctMap, new: &RefunctMap) -> Vec<MapChange> {
+    let mut result = Vec::new();
+    result.extend(compare(ChangeLocation::Map, Some(map_properties(old)), Some(map_properties(new))));
+    let cluster_count = old.clusters.len().max(new.clusters.len());
+    for cluster_index in 0..cluster_count {
+        let (old, new) = (old.clusters.get(cluster_index), new.clusters.get(cluster_index));
+        result.extend(compare(ChangeLocation::Cluster(cluster_index), old.map(cluster_properties), new.map(cluster_properties)));
+        for element_type in ElementType::ALL {
+            let old_list = old.map(|c| c.elements(element_type).as_slice()).unwrap_or_default();
+            let new_list = new.map(|c| c.elements(element_type).as_slice()).unwrap_or_default();
+            for element_index in 0..old_list.len().max(new_list.len()) {
+                let location = ChangeLocation::Element(ElementIndex { cluster_index, element_type, element_index });
+                result.extend(compare(location, old_list.get(element_index).map(to_value), new_list.get(element_index).map(to_value)));
+            }
+        }
+    }
+    result
+}
+
+/// Three-way merge of leaf properties, keeping ours on conflicts
+fn merge_value(location: ChangeLocation, path: &str, base: &Value, ours: &Value, theirs: &Value, conflicts: &mut Vec<Conflict>) -> Value {
+    if let (Value::Object(base), Value::Object(ours), Value::Object(theirs)) = (base, ours, theirs) {
+        let keys: BTreeSet<_> = base.keys().chain(ours.keys()).chain(theirs.keys()).collect();
+        let merged = keys.into_iter().map(|key| {
+            let get = |object: &serde_json::Map<String, Value>| object.get(key).cloned().unwrap_or(Value::Null);
+            let value = merge_value(location, &join(path, key), &get(base), &get(ours), &get(theirs), conflicts);
+            (key.clone(), value)
+        }).collect();
+        return Value::Object(merged);
+    }
+    if ours == theirs || theirs == base {
+        ours.clone()
+    } else if ours == base {
+        theirs.clone()
+    } else {
+        conflicts.push(Conflict {
+            location,
+            property: path.to_string(),
+            base: base.to_string(),
+            ours: ours.to_string(),
+            theirs: theirs.to_string(),
+        });
+        ours.clone()
+    }
+}
+
+/// Three-way merge of the elements of one type of a cluster.
+///
+/// Each index is merged on its own: changes of only one side are taken, elements modified on
+/// both sides are merged per property, and conflicting changes keep our version.
+fn merge_elements(
+    cluster_index: usize, element_type: ElementType,
+    base: &[Element], ours: &[Element], theirs: &[Element], conflicts: &mut Vec<Conflict>,
+) -> Vec<Element> {
+    let len = base.len().max(ours.len()).max(theirs.len());
+    let mut merged = Vec::new();
+    for element_index in 0..len {
+        let location = ChangeLocation::Element(ElementIndex { cluster_index, element_type, element_index });
+        let slot = |list: &[Element]| list.get(element_index).map(to_value).unwrap_or(Value::Null);
+        let (base, ours, theirs) = (slot(base), slot(ours), slot(theirs));
+        let element = if base.is_null() || ours.is_null() || theirs.is_null() {
+            // added or removed on at least one side, which can't be merged per property
+            merge_value(location, "element", &base, &ours, &theirs, conflicts)
+        } else {
+            merge_value(location, "", &base, &ours, &theirs, conflicts)
+        };
+        if element.is_null() {
+            // removing an element only works at the end of the list, otherwise later indices would shift
+            merged.push(None);
+        } else {
+            merged.push(Some(serde_json::from_value::<Element>(element).unwrap()));
+        }
+    }
+    while let Some(None) = merged.last() {
+        merged.pop();
+    }
+    merged.into_iter().enumerate().map(|(element_index, element)| element.unwrap_or_else(|| {
+        // one side removed the element while the other one added elements after it, keep the base version
+        let location = ChangeLocation::Element(ElementIndex { cluster_index, element_type, element_index });
+        conflicts.push(Conflict {
+            location,
+            property: "element".to_string(),
+            base: to_value(&base[element_index]).to_string(),
+            ours: "removed".to_string(),
+            theirs: "needed for elements added after it".to_string(),
+        });
+        base[element_index].clone()
+    })).collect()
+}
+
+/// Three-way merge of `ours` and `theirs`, which were both edited starting from `base`,
+/// usually `Tas::original_map()`.
+pub fn merge(base: &RefunctMap, ours: &RefunctMap, theirs: &RefunctMap) -> Result<MergeResult, MapError> {
+    let cluster_count = base.clusters.len();
+    if ours.clusters.len() != cluster_count || theirs.clusters.len() != cluster_count {
+        return Err(MapError::ClusterCountMismatch);
+    }
+    let mut conflicts = Vec::new();
+    let properties = merge_value(ChangeLocation::Map, "", &map_properties(base), &map_properties(ours), &map_properties(theirs), &mut conflicts);
+    let mut map = RefunctMap {
+        version: ours.version,
+        metadata: serde_json::from_value(properties["metadata"].clone()).unwrap(),
+        spawn: serde_json::from_value(properties["spawn"].clone()).unwrap(),
+        world: serde_json::from_value(properties["world"].clone()).unwrap(),
+        triggers: serde_json::from_value(properties["triggers"].clone()).unwrap(),
+        clusters: Vec::new(),
+    };
+    for (cluster_index, ((base, ours), theirs)) in base.clusters.iter().zip(&ours.clusters).zip(&theirs.clusters).enumerate() {
+        let location = ChangeLocation::Cluster(cluster_index);
+        let properties = merge_value(location, "", &cluster_properties(base), &cluster_properties(ours), &cluster_properties(theirs), &mut conflicts);
+        let mut cluster = Cluster {
+            z: serde_json::from_value(properties["z"].clone()).unwrap(),
+            rise_speed: serde_json::from_value(properties["rise_speed"].clone()).unwrap(),
+            platforms: Vec::new(),
+            cubes: Vec::new(),
+            buttons: Vec::new(),
+            lifts: Vec::new(),
+            pipes: Vec::new(),
+            springpads: Vec::new(),
+        };
+        for element_type in ElementType::ALL {
+            *cluster.elements_mut(element_type) = merge_elements(
+                cluster_index, element_type,
+                base.elements(element_type), ours.elements(element_type), theirs.elements(element_type),
+                &mut conflicts,
+            );
+        }
+        map.clusters.push(cluster);
+    }
+    Ok(MergeResult { map, conflicts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{element, test_map};
+
+    #[test]
+    fn diff_reports_changed_properties_and_elements() {
+        let old = test_map();
+        let mut new = old.clone();
+        new.clusters[1].rise_speed = 200.;
+        new.clusters[0].platforms[1].x = 42.;
+        new.clusters[0].platforms[1].hidden = true;
+        new.clusters[1].cubes.push(element(1., 2., 3., 0., 0., 0.));
+        new.clusters[0].springpads.clear();
+        new.world.kill_z = Some(-5000.);
+        new.triggers.clear();
+        let index = |cluster_index, element_type, element_index| ChangeLocation::Element(ElementIndex { cluster_index, element_type, element_index });
+        let change = |property: &str, old: &str, new: &str| PropertyChange { property: property.to_string(), old: old.to_string(), new: new.to_string() };
+        assert_eq!(diff(&old, &new), vec![
+            MapChange { location: ChangeLocation::Map, kind: ChangeKind::Modified(vec![
+                change("triggers", r#"[{"button_cluster_index":0,"button_index":0,"clusters":[1]}]"#, "[]"),
+                change("world.kill_z", "null", "-5000.0"),
+            ])},
+            MapChange { location: index(0, ElementType::Platform, 1), kind: ChangeKind::Modified(vec![
+                change("hidden", "false", "true"),
+                change("x", "30.5", "42.0"),
+            ])},
+            MapChange { location: index(0, ElementType::Springpad, 0), kind: ChangeKind::Removed },
+            MapChange { location: ChangeLocation::Cluster(1), kind: ChangeKind::Modified(vec![change("rise_speed", "100.0", "200.0")]) },
+            MapChange { location: index(1, ElementType::Cube, 1), kind: ChangeKind::Added },
+        ]);
+        assert_eq!(diff(&old, &old), vec![]);
+    }
+
+    #[test]
+    fn merge_combines_changes_and_reports_conflicts() {
+        let base = test_map();
+        let mut ours = base.clone();
+        let mut theirs = base.clone();
+        ours.clusters[0].platforms[0].x = 1.;
+        theirs.clusters[0].platforms[0].y = 2.;
+        ours.clusters[1].z = 10.;
+        theirs.clusters[1].z = 20.;
+        theirs.metadata.description = "merged".to_string();
+        ours.clusters[0].cubes.push(element(1., 1., 1., 0., 0., 0.));
+        theirs.clusters[1].buttons.push(element(2., 2., 2., 0., 0., 0.));
+        theirs.clusters[1].springpads.clear();
+
+        let MergeResult { map, conflicts } = merge(&base, &ours, &theirs).unwrap();
+        assert_eq!((map.clusters[0].platforms[0].x, map.clusters[0].platforms[0].y), (1., 2.));
+        assert_eq!(map.metadata.description, "merged");
+        assert_eq!(map.clusters[0].cubes.len(), 2);
+        assert_eq!(map.clusters[1].buttons.len(), 2);
+        assert!(map.clusters[1].springpads.is_empty());
+        assert_eq!(map.clusters[1].z, 10.);
+        assert_eq!(conflicts, vec![Conflict {
+            location: ChangeLocation::Cluster(1),
+            property: "z".to_string(),
+            base: "-1000.0".to_string(),
+            ours: "10.0".to_string(),
+            theirs: "20.0".to_string(),
+        }]);
+    }
+}
@@ -0,0 +1,140 @@
+//! glTF 2.0 (`.gltf` with embedded buffer) export and import.
+//!
+//! Every element is a node named like [`ElementIndex::object_name`] instancing the same unit cube mesh,
+//! with the element's transform as the node's translation, rotation and scale.
+
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::json;
+use crate::{ElementIndex, MapError, RefunctMap, Transform};
+
+/// Corners of the unit cube, the local x, y and z being bits 0, 1 and 2 of the index
+const CORNERS: [[f32; 3]; 8] = [
+    [0., 0., 0.], [1., 0., 0.], [0., 1., 0.], [1., 1., 0.],
+    [0., 0., 1.], [1., 0., 1.], [0., 1., 1.], [1., 1., 1.],
+];
+/// Counter-clockwise quads of the cube's faces, -x, +x, -y, +y, -z, +z
+pub(crate) const FACES: [[usize; 4]; 6] = [
+    [0, 4, 6, 2], [1, 3, 7, 5],
+    [0, 1, 5, 4], [2, 6, 7, 3],
+    [0, 2, 3, 1], [4, 5, 7, 6],
+];
+
+pub fn export(map: &RefunctMap) -> String {
+    let mut buffer = Vec::new();
+    for corner in CORNERS {
+        for c in corner {
+            buffer.extend_from_slice(&c.to_le_bytes());
+        }
+    }
+    let positions_len = buffer.len();
+    for [a, b, c, d] in FACES {
+        for index in [a, b, c, a, c, d] {
+            buffer.extend_from_slice(&(index as u16).to_le_bytes());
+        }
+    }
+    let indices_len = buffer.len() - positions_len;
+
+    let nodes: Vec<_> = map.elements().map(|(index, element)| {
+        let transform = Transform::from_element(element);
+        json!({
+            "name": index.object_name(),
+            "mesh": 0,
+            "translation": transform.translation,
+            "rotation": transform.quaternion(),
+            "scale": transform.scale,
+        })
+    }).collect();
+    let gltf = json!({
+        "asset": { "version": "2.0", "generator": "refunct-tas" },
+        "scene": 0,
+        "scenes": [{ "name": map.metadata.name, "nodes": (0..nodes.len()).collect::<Vec<_>>() }],
+        "nodes": nodes,
+        "meshes": [{ "name": "element", "primitives": [{ "attributes": { "POSITION": 0 }, "indices": 1 }] }],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": CORNERS.len(), "type": "VEC3", "min": [0., 0., 0.], "max": [1., 1., 1.] },
+            { "bufferView": 1, "componentType": 5123, "count": FACES.len() * 6, "type": "SCALAR" },
+        ],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": positions_len, "target": 34962 },
+            { "buffer": 0, "byteOffset": positions_len, "byteLength": indices_len, "target": 34963 },
+        ],
+        "buffers": [{
+            "byteLength": buffer.len(),
+            "uri": format!("data:application/octet-stream;base64,{}", base64::engine::general_purpose::STANDARD.encode(&buffer)),
+        }],
+    });
+    serde_json::to_string_pretty(&gltf).unwrap()
+}
+
+#[derive(Deserialize)]
+struct Gltf {
+    #[serde(default)]
+    nodes: Vec<Node>,
+}
+#[derive(Deserialize)]
+struct Node {
+    name: Option<String>,
+    translation: Option<[f64; 3]>,
+    rotation: Option<[f64; 4]>,
+    scale: Option<[f64; 3]>,
+    /// column-major, used by some tools instead of translation, rotation and scale
+    matrix: Option<[f64; 16]>,
+}
+
+/// Read the transforms of all nodes named after an element.
+///
+/// Other nodes like cameras or lights are ignored.
+/// Element nodes must not be children of transformed nodes, as parent transforms aren't applied.
+pub fn import(gltf: &str) -> Result<Vec<(ElementIndex, Transform)>, MapError> {
+    let gltf: Gltf = serde_json::from_str(gltf)?;
+    let mut transforms = Vec::new();
+    for node in gltf.nodes {
+        let Some(index) = node.name.as_deref().and_then(|name| name.parse::<ElementIndex>().ok()) else { continue };
+        let transform = match node.matrix {
+            Some(m) => {
+                Transform::from_edges([m[12], m[13], m[14]], [[m[0], m[1], m[2]], [m[4], m[5], m[6]], [m[8], m[9], m[10]]])
+                    .ok_or_else(|| MapError::InvalidModel(format!("node {} has a degenerate matrix", index.object_name())))?
+            }
+            None => Transform::from_quaternion(
+                node.translation.unwrap_or([0.; 3]),
+                node.rotation.unwrap_or([0., 0., 0., 1.]),
+                node.scale.unwrap_or([1.; 3]),
+            ),
+        };
+        transforms.push((index, transform));
+    }
+    Ok(transforms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{assert_maps_approx_eq, test_map};
+
+    #[test]
+    fn round_trip() {
+        let map = test_map();
+        let gltf = export(&map);
+        let imported = map.with_transforms(import(&gltf).unwrap()).unwrap();
+        assert_maps_approx_eq(&map, &imported);
+    }
+
+    #[test]
+    fn import_applies_edited_transforms() {
+        let map = test_map();
+        let gltf = r#"{"nodes": [
+            {"name": "Camera", "translation": [1, 2, 3]},
+            {"name": "c1_cube_0", "translation": [1, 2, 3], "scale": [0.5, 1.5, 2.5]}
+        ]}"#;
+        let imported = map.with_transforms(import(gltf).unwrap()).unwrap();
+        let cube = &imported.clusters[1].cubes[0];
+        assert_eq!((cube.x, cube.y, cube.z), (100., 300., 200.));
+        assert_eq!((cube.pitch, cube.yaw, cube.roll), (0., 0., 0.));
+        assert_eq!((cube.sizex, cube.sizey, cube.sizez), (50., 250., 150.));
+        assert_eq!(imported.clusters[0].cubes[0], map.clusters[0].cubes[0]);
+
+        let unknown = r#"{"nodes": [{"name": "c5_cube_0"}]}"#;
+        assert!(matches!(map.with_transforms(import(unknown).unwrap()), Err(MapError::UnknownElement(_))));
+    }
+}
@@ -0,0 +1,411 @@
+//! Offline handling of the map files written by `Tas::save_map`.
+//!
+//! The types mirror `RefunctMap` of rtil, which can't be used outside of the game.
+//! A test compares both definitions, so a change to rtil's schema fails here until it's mirrored.
+//! Maps can be converted to OBJ and glTF models with one box per element to edit them in
+//! external 3D tools, and the edited transforms can be imported back into the map.
+//! Maps edited by different people can be compared and merged with [`diff`].
+
+use std::fmt::{Display, Formatter};
+use std::io::{Read, Write};
+use std::str::FromStr;
+use serde::{Serialize, Deserialize};
+
+mod transform;
+pub mod obj;
+pub mod gltf;
+pub mod diff;
+
+pub use transform::Transform;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefunctMap {
+    pub version: u32,
+    pub metadata: MapMetadata,
+    pub spawn: Option<SpawnPoint>,
+    pub world: MapWorldSettings,
+    #[serde(default)]
+    pub triggers: Vec<ClusterTrigger>,
+    pub clusters: Vec<Cluster>,
+}
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MapMetadata {
+    pub name: String,
+    pub author: String,
+    pub description: String,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpawnPoint {
+    pub location: Location,
+    pub rotation: Rotation,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Rotation {
+    pub pitch: f32,
+    pub yaw: f32,
+    pub roll: f32,
+}
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MapWorldSettings {
+    pub gravity: Option<f32>,
+    pub kill_z: Option<f32>,
+    pub time_of_day: Option<f32>,
+}
+/// Pressing the button raises the given clusters instead of the game's order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClusterTrigger {
+    pub button_cluster_index: usize,
+    pub button_index: usize,
+    pub clusters: Vec<usize>,
+}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cluster {
+    pub z: f32,
+    pub rise_speed: f32,
+    pub platforms: Vec<Element>,
+    pub cubes: Vec<Element>,
+    pub buttons: Vec<Element>,
+    pub lifts: Vec<Element>,
+    pub pipes: Vec<Element>,
+    pub springpads: Vec<Element>,
+}
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Element {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub pitch: f32,
+    pub yaw: f32,
+    pub roll: f32,
+    pub sizex: f32,
+    pub sizey: f32,
+    pub sizez: f32,
+    #[serde(default)]
+    pub hidden: bool,
+    /// springpads only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub launch_velocity: Option<f32>,
+    /// pipes only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pipe_exit: Option<PipeExit>,
+    /// lifts only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lift_distance: Option<f32>,
+    /// lifts only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lift_speed: Option<f32>,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PipeExit {
+    pub cluster_index: usize,
+    pub pipe_index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ElementType {
+    Platform,
+    Cube,
+    Button,
+    Lift,
+    Pipe,
+    Springpad,
+}
+impl ElementType {
+    pub const ALL: [ElementType; 6] = [
+        ElementType::Platform, ElementType::Cube, ElementType::Button,
+        ElementType::Lift, ElementType::Pipe, ElementType::Springpad,
+    ];
+    pub fn name(self) -> &'static str {
+        match self {
+            ElementType::Platform => "platform",
+            ElementType::Cube => "cube",
+            ElementType::Button => "button",
+            ElementType::Lift => "lift",
+            ElementType::Pipe => "pipe",
+            ElementType::Springpad => "springpad",
+        }
+    }
+}
+impl Display for ElementType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Zero-based index of an element like `ElementIndex` in rtil
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ElementIndex {
+    pub cluster_index: usize,
+    pub element_type: ElementType,
+    pub element_index: usize,
+}
+impl ElementIndex {
+    /// Name of the element's box in exported models, e.g. `c3_platform_0`
+    pub fn object_name(&self) -> String {
+        format!("c{}_{}_{}", self.cluster_index, self.element_type, self.element_index)
+    }
+}
+impl FromStr for ElementIndex {
+    type Err = ();
+
+    /// Parse an object name created by [`ElementIndex::object_name`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('_');
+        let cluster_index = parts.next().and_then(|c| c.strip_prefix('c')).and_then(|c| c.parse().ok()).ok_or(())?;
+        let element_type = parts.next().and_then(|t| ElementType::ALL.into_iter().find(|typ| typ.name() == t)).ok_or(())?;
+        let element_index = parts.next().and_then(|i| i.parse().ok()).ok_or(())?;
+        match parts.next() {
+            Some(_) => Err(()),
+            None => Ok(ElementIndex { cluster_index, element_type, element_index }),
+        }
+    }
+}
+impl Display for ElementIndex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cluster {} {} {}", self.cluster_index, self.element_type, self.element_index)
+    }
+}
+
+impl Cluster {
+    pub fn elements(&self, element_type: ElementType) -> &Vec<Element> {
+        match element_type {
+            ElementType::Platform => &self.platforms,
+            ElementType::Cube => &self.cubes,
+            ElementType::Button => &self.buttons,
+            ElementType::Lift => &self.lifts,
+            ElementType::Pipe => &self.pipes,
+            ElementType::Springpad => &self.springpads,
+        }
+    }
+    pub fn elements_mut(&mut self, element_type: ElementType) -> &mut Vec<Element> {
+        match element_type {
+            ElementType::Platform => &mut self.platforms,
+            ElementType::Cube => &mut self.cubes,
+            ElementType::Button => &mut self.buttons,
+            ElementType::Lift => &mut self.lifts,
+            ElementType::Pipe => &mut self.pipes,
+            ElementType::Springpad => &mut self.springpads,
+        }
+    }
+}
+
+impl RefunctMap {
+    /// All elements of all clusters, ordered by cluster, type and index
+    pub fn elements(&self) -> impl Iterator<Item = (ElementIndex, &Element)> + '_ {
+        self.clusters.iter().enumerate().flat_map(|(cluster_index, cluster)| {
+            ElementType::ALL.into_iter().flat_map(move |element_type| {
+                cluster.elements(element_type).iter().enumerate().map(move |(element_index, element)| {
+                    (ElementIndex { cluster_index, element_type, element_index }, element)
+                })
+            })
+        })
+    }
+    pub fn element(&self, index: ElementIndex) -> Option<&Element> {
+        self.clusters.get(index.cluster_index)?.elements(index.element_type).get(index.element_index)
+    }
+    pub fn element_mut(&mut self, index: ElementIndex) -> Option<&mut Element> {
+        self.clusters.get_mut(index.cluster_index)?.elements_mut(index.element_type).get_mut(index.element_index)
+    }
+
+    /// Replace the transforms of the given elements, keeping everything else of this map
+    pub fn with_transforms(&self, transforms: impl IntoIterator<Item = (ElementIndex, Transform)>) -> Result<RefunctMap, MapError> {
+        let mut map = self.clone();
+        let mut seen = Vec::new();
+        for (index, transform) in transforms {
+            if seen.contains(&index) {
+                return Err(MapError::DuplicateElement(index));
+            }
+            seen.push(index);
+            let element = map.element_mut(index).ok_or(MapError::UnknownElement(index))?;
+            *element = transform.to_element(element);
+        }
+        Ok(map)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum MapError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("map version {0} can't be converted offline, load and save it in the in-game map editor first")]
+    UnsupportedVersion(u32),
+    #[error("invalid model: {0}")]
+    InvalidModel(String),
+    #[error("the model contains {0}, which doesn't exist in the map")]
+    UnknownElement(ElementIndex),
+    #[error("the model contains {0} multiple times")]
+    DuplicateElement(ElementIndex),
+    #[error("the maps have different numbers of clusters")]
+    ClusterCountMismatch,
+}
+
+/// Read a map file, migrating v1 maps to v2.
+///
+/// v0 maps store relative scales instead of sizes, which can only be migrated in-game.
+pub fn read_map(mut reader: impl Read) -> Result<RefunctMap, MapError> {
+    #[derive(Deserialize)]
+    struct Version {
+        #[serde(default)]
+        version: u32,
+    }
+    #[derive(Deserialize)]
+    struct RefunctMapV1 {
+        clusters: Vec<Cluster>,
+    }
+    let mut content = String::new();
+    reader.read_to_string(&mut content)?;
+    let version: Version = serde_json::from_str(&content)?;
+    match version.version {
+        1 => {
+            let map: RefunctMapV1 = serde_json::from_str(&content)?;
+            Ok(RefunctMap {
+                version: 2,
+                metadata: MapMetadata::default(),
+                spawn: None,
+                world: MapWorldSettings::default(),
+                triggers: Vec::new(),
+                clusters: map.clusters,
+            })
+        }
+        2 => Ok(serde_json::from_str(&content)?),
+        version => Err(MapError::UnsupportedVersion(version)),
+    }
+}
+
+pub fn write_map(writer: impl Write, map: &RefunctMap) -> Result<(), MapError> {
+    serde_json::to_writer_pretty(writer, map)?;
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn element(x: f32, y: f32, z: f32, pitch: f32, yaw: f32, roll: f32) -> Element {
+        Element {
+            x, y, z, pitch, yaw, roll, sizex: 500., sizey: 250., sizez: 125., hidden: false,
+            launch_velocity: None, pipe_exit: None, lift_distance: None, lift_speed: None,
+        }
+    }
+
+    pub(crate) fn test_map() -> RefunctMap {
+        let cluster = |z| Cluster {
+            z,
+            rise_speed: 100.,
+            platforms: vec![element(-1000., 250., 0., 0., 0., 0.), element(30.5, -4000., 12., 10., 90., -20.)],
+            cubes: vec![element(0., 0., 100., 0., -135., 0.)],
+            buttons: vec![element(75., 20., -3.25, -45., 170., 60.)],
+            lifts: vec![],
+            pipes: vec![],
+            springpads: vec![element(800., 800., 0., 0., 45., 0.)],
+        };
+        RefunctMap {
+            version: 2,
+            metadata: MapMetadata { name: "test".to_string(), author: "someone".to_string(), description: String::new() },
+            spawn: None,
+            world: MapWorldSettings { gravity: Some(-1960.), kill_z: None, time_of_day: None },
+            triggers: vec![ClusterTrigger { button_cluster_index: 0, button_index: 0, clusters: vec![1] }],
+            clusters: vec![cluster(-500.), cluster(-1000.)],
+        }
+    }
+
+    pub(crate) fn assert_maps_approx_eq(a: &RefunctMap, b: &RefunctMap) {
+        fn approx(a: f32, b: f32) -> bool {
+            (a - b).abs() < 1e-2
+        }
+        assert_eq!(a.elements().count(), b.elements().count());
+        for ((index, a), (_, b)) in a.elements().zip(b.elements()) {
+            let same = approx(a.x, b.x) && approx(a.y, b.y) && approx(a.z, b.z)
+                && approx(a.pitch, b.pitch) && approx(a.yaw, b.yaw) && approx(a.roll, b.roll)
+                && approx(a.sizex, b.sizex) && approx(a.sizey, b.sizey) && approx(a.sizez, b.sizez)
+                && a.hidden == b.hidden;
+            assert!(same, "{index} differs: {a:?} != {b:?}");
+        }
+        assert_eq!(a.metadata, b.metadata);
+        assert_eq!(a.world, b.world);
+    }
+
+    #[test]
+    fn object_names_round_trip() {
+        for (index, _) in test_map().elements() {
+            assert_eq!(index.object_name().parse(), Ok(index));
+        }
+        assert_eq!("c1_platform_2.001".parse::<ElementIndex>(), Err(()));
+        assert_eq!("Camera".parse::<ElementIndex>(), Err(()));
+    }
+
+    #[test]
+    fn reads_v1_maps() {
+        let v1 = r#"{"version":1,"clusters":[{"z":1.0,"rise_speed":2.0,"platforms":[{"x":1.0,"y":2.0,"z":3.0,"pitch":0.0,"yaw":0.0,"roll":0.0,"sizex":1.0,"sizey":1.0,"sizez":1.0}],"cubes":[],"buttons":[],"lifts":[],"pipes":[],"springpads":[]}]}"#;
+        let map = read_map(v1.as_bytes()).unwrap();
+        assert_eq!(map.version, 2);
+        assert_eq!(map.clusters[0].platforms[0].x, 1.);
+        assert!(!map.clusters[0].platforms[0].hidden);
+        assert!(matches!(read_map(r#"{"clusters":[]}"#.as_bytes()), Err(MapError::UnsupportedVersion(0))));
+    }
+
+    #[test]
+    fn element_params_are_only_written_if_set() {
+        let mut map = test_map();
+        map.clusters[0].springpads[0].launch_velocity = Some(2500.);
+        let mut json = Vec::new();
+        write_map(&mut json, &map).unwrap();
+        let json = String::from_utf8(json).unwrap();
+        assert_eq!(json.matches("launch_velocity").count(), 1);
+        assert!(!json.contains("pipe_exit"));
+        assert_eq!(read_map(json.as_bytes()).unwrap(), map);
+    }
+
+    /// Structs of the map schema, which must be defined identically here and in rtil
+    const SCHEMA: &[&str] = &[
+        "RefunctMap", "MapMetadata", "SpawnPoint", "Location", "Rotation", "MapWorldSettings",
+        "ClusterTrigger", "Cluster", "Element", "PipeExit",
+    ];
+    const RTIL_SOURCE: &str = include_str!("../../rtil/src/threads/ue/rebo/rebo_init.rs");
+
+    /// `#[serde]` attributes, name and type of every field of `struct name` in `source`,
+    /// ignoring visibility and comments
+    fn struct_fields(source: &str, name: &str) -> Vec<(Vec<String>, String, String)> {
+        let header = format!("struct {name} {{");
+        assert!(source.lines().any(|line| line.contains(&header)), "struct {name} not found");
+        let lines = source.lines()
+            .skip_while(|line| !line.trim_start().trim_start_matches("pub ").trim_start_matches("pub(super) ").starts_with(&header))
+            .skip(1);
+        let mut fields = Vec::new();
+        let mut attributes = Vec::new();
+        for line in lines {
+            let line = line.trim();
+            if line == "}" {
+                break;
+            }
+            if line.starts_with("//") {
+                continue;
+            }
+            if line.starts_with("#[serde") {
+                attributes.push(line.to_string());
+                continue;
+            }
+            let line = ["pub(super) ", "pub(crate) ", "pub "].iter()
+                .fold(line, |line, vis| line.strip_prefix(vis).unwrap_or(line));
+            let (field, typ) = line.split_once(':').unwrap_or_else(|| panic!("can't parse field {line:?} of {name}"));
+            fields.push((std::mem::take(&mut attributes), field.trim().to_string(), typ.trim().trim_end_matches(',').to_string()));
+        }
+        fields
+    }
+
+    #[test]
+    fn rtil_schema_matches() {
+        let source = include_str!("lib.rs");
+        for name in SCHEMA {
+            assert_eq!(struct_fields(source, name), struct_fields(RTIL_SOURCE, name), "{name} differs from rtil's definition");
+        }
+    }
+}
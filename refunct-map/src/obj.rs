@@ -0,0 +1,97 @@
+//! Wavefront OBJ export and import.
+//!
+//! Every element is an object named like [`ElementIndex::object_name`] with the 8 corners of its box
+//! in world space. As OBJ doesn't have transforms, they are reconstructed from the corners on import,
+//! which requires the 3D tool to keep the order of the vertices.
+
+use std::fmt::Write;
+use crate::gltf::FACES;
+use crate::{ElementIndex, MapError, RefunctMap, Transform};
+
+pub fn export(map: &RefunctMap) -> String {
+    let mut obj = format!("# {:?} exported by refunct-tas\n", map.metadata.name);
+    let mut vertex_count = 0;
+    for (index, element) in map.elements() {
+        let transform = Transform::from_element(element);
+        writeln!(obj, "o {}", index.object_name()).unwrap();
+        for corner in 0..8 {
+            let [x, y, z] = transform.corner(corner);
+            writeln!(obj, "v {x} {y} {z}").unwrap();
+        }
+        for face in FACES {
+            let [a, b, c, d] = face.map(|corner| vertex_count + corner + 1);
+            writeln!(obj, "f {a} {b} {c} {d}").unwrap();
+        }
+        vertex_count += 8;
+    }
+    obj
+}
+
+/// Read the transforms of all objects named after an element.
+///
+/// Other objects are ignored.
+pub fn import(obj: &str) -> Result<Vec<(ElementIndex, Transform)>, MapError> {
+    // (element, vertices declared after the object / group statement)
+    let mut objects: Vec<(Option<ElementIndex>, Vec<[f64; 3]>)> = Vec::new();
+    for (line_number, line) in obj.lines().enumerate() {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("o" | "g") => {
+                let name = parts.next().unwrap_or_default();
+                objects.push((name.parse().ok(), Vec::new()));
+            }
+            Some("v") => {
+                let coords: Vec<f64> = parts.take(3).map(str::parse).collect::<Result<_, _>>()
+                    .map_err(|e| MapError::InvalidModel(format!("line {}: invalid vertex: {e}", line_number + 1)))?;
+                let &[x, y, z] = coords.as_slice() else {
+                    return Err(MapError::InvalidModel(format!("line {}: vertex needs 3 coordinates", line_number + 1)));
+                };
+                if let Some((_, vertices)) = objects.last_mut() {
+                    vertices.push([x, y, z]);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    let mut transforms = Vec::new();
+    for (index, vertices) in objects {
+        let Some(index) = index else { continue };
+        let invalid = |what: &str| MapError::InvalidModel(format!("object {} {what}", index.object_name()));
+        if vertices.len() < 8 {
+            return Err(invalid("has less than 8 vertices"));
+        }
+        let origin = vertices[0];
+        let edge = |corner: usize| [0, 1, 2].map(|i| vertices[corner][i] - origin[i]);
+        let transform = Transform::from_edges(origin, [edge(1), edge(2), edge(4)])
+            .ok_or_else(|| invalid("is degenerate"))?;
+        transforms.push((index, transform));
+    }
+    Ok(transforms)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{assert_maps_approx_eq, test_map};
+
+    #[test]
+    fn round_trip() {
+        let map = test_map();
+        let obj = export(&map);
+        let imported = map.with_transforms(import(&obj).unwrap()).unwrap();
+        assert_maps_approx_eq(&map, &imported);
+    }
+
+    #[test]
+    fn import_ignores_other_objects_and_attributes() {
+        let map = test_map();
+        let obj = "mtllib foo.mtl\no Plane\nv 0 0 0\no c0_button_0\n\
+            v 1 0 2\nv 2 0 2\nv 1 1 2\nv 2 1 2\nv 1 0 3\nv 2 0 3\nv 1 1 3\nv 2 1 3\nvn 0 1 0\ns off\nf 2 3 4\n";
+        let imported = map.with_transforms(import(obj).unwrap()).unwrap();
+        let button = &imported.clusters[0].buttons[0];
+        assert_eq!((button.x, button.y, button.z), (100., 200., 0.));
+        assert_eq!((button.sizex, button.sizey, button.sizez), (100., 100., 100.));
+        assert_eq!(imported.clusters[0].platforms, map.clusters[0].platforms);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::Element;
+
+/// UE uses centimeters in a left-handed Z-up coordinate system,
+/// OBJ and glTF use meters in a right-handed Y-up one.
+const UNITS_PER_METER: f64 = 100.;
+
+/// Transform of an element's box in model space.
+///
+/// The box is the unit cube from `(0, 0, 0)` to `(1, 1, 1)`, scaled by `scale`, rotated such that
+/// the local x, y and z axes point along `axes` and moved to `translation`.
+/// Thus, an element's location is a corner of its box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transform {
+    pub translation: [f64; 3],
+    /// unit vectors the local x, y and z axes are rotated to
+    pub axes: [[f64; 3]; 3],
+    pub scale: [f64; 3],
+}
+
+/// Swap Y and Z to convert between UE and model space, which is its own inverse
+fn swap([x, y, z]: [f64; 3]) -> [f64; 3] {
+    [x, z, y]
+}
+
+impl Transform {
+    pub fn from_element(e: &Element) -> Transform {
+        let [ue_x, ue_y, ue_z] = rotator_to_axes(e.pitch as f64, e.yaw as f64, e.roll as f64);
+        let location = [e.x as f64, e.y as f64, e.z as f64].map(|c| c / UNITS_PER_METER);
+        Transform {
+            translation: swap(location),
+            // the model's local y axis is UE's local Z axis and vice versa
+            axes: [swap(ue_x), swap(ue_z), swap(ue_y)],
+            scale: [e.sizex as f64, e.sizez as f64, e.sizey as f64].map(|s| s / UNITS_PER_METER),
+        }
+    }
+
+    /// Apply this transform to `base`, keeping all other properties like `hidden`
+    pub fn to_element(&self, base: &Element) -> Element {
+        let [x, y, z] = swap(self.translation).map(|c| (c * UNITS_PER_METER) as f32);
+        let [model_x, model_y, model_z] = self.axes;
+        let (pitch, yaw, roll) = axes_to_rotator([swap(model_x), swap(model_z), swap(model_y)]);
+        let [sizex, sizez, sizey] = self.scale.map(|s| (s * UNITS_PER_METER) as f32);
+        Element {
+            x, y, z,
+            pitch: pitch as f32, yaw: yaw as f32, roll: roll as f32,
+            sizex, sizey, sizez,
+            ..base.clone()
+        }
+    }
+
+    /// Create a transform from the box's corner at local `(0, 0, 0)` and its edges along the local axes.
+    ///
+    /// Edges which aren't perpendicular (e.g. due to shearing) are made perpendicular.
+    pub fn from_edges(origin: [f64; 3], edges: [[f64; 3]; 3]) -> Option<Transform> {
+        let scale = edges.map(length);
+        if scale.iter().any(|&s| s < 1e-9) {
+            return None;
+        }
+        let x = normalize(edges[0]);
+        let y = normalize(sub(edges[1], mul(x, dot(x, edges[1]))));
+        let z = cross(x, y);
+        Some(Transform { translation: origin, axes: [x, y, z], scale })
+    }
+
+    /// Model-space position of a corner of the box, `corner` having the local x, y and z in bits 0, 1 and 2
+    pub fn corner(&self, corner: usize) -> [f64; 3] {
+        let mut pos = self.translation;
+        for axis in 0..3 {
+            if corner & (1 << axis) != 0 {
+                pos = add(pos, mul(self.axes[axis], self.scale[axis]));
+            }
+        }
+        pos
+    }
+
+    /// Rotation as quaternion `[x, y, z, w]`
+    pub fn quaternion(&self) -> [f64; 4] {
+        // rotation matrix m[row][col] with the axes as columns
+        let m = |row: usize, col: usize| self.axes[col][row];
+        let trace = m(0, 0) + m(1, 1) + m(2, 2);
+        if trace > 0. {
+            let s = (trace + 1.).sqrt() * 2.;
+            [(m(2, 1) - m(1, 2)) / s, (m(0, 2) - m(2, 0)) / s, (m(1, 0) - m(0, 1)) / s, s / 4.]
+        } else if m(0, 0) > m(1, 1) && m(0, 0) > m(2, 2) {
+            let s = (1. + m(0, 0) - m(1, 1) - m(2, 2)).sqrt() * 2.;
+            [s / 4., (m(0, 1) + m(1, 0)) / s, (m(0, 2) + m(2, 0)) / s, (m(2, 1) - m(1, 2)) / s]
+        } else if m(1, 1) > m(2, 2) {
+            let s = (1. + m(1, 1) - m(0, 0) - m(2, 2)).sqrt() * 2.;
+            [(m(0, 1) + m(1, 0)) / s, s / 4., (m(1, 2) + m(2, 1)) / s, (m(0, 2) - m(2, 0)) / s]
+        } else {
+            let s = (1. + m(2, 2) - m(0, 0) - m(1, 1)).sqrt() * 2.;
+            [(m(0, 2) + m(2, 0)) / s, (m(1, 2) + m(2, 1)) / s, s / 4., (m(1, 0) - m(0, 1)) / s]
+        }
+    }
+
+    pub fn from_quaternion(translation: [f64; 3], [x, y, z, w]: [f64; 4], scale: [f64; 3]) -> Transform {
+        let len = (x * x + y * y + z * z + w * w).sqrt();
+        let (x, y, z, w) = (x / len, y / len, z / len, w / len);
+        let axes = [
+            [1. - 2. * (y * y + z * z), 2. * (x * y + z * w), 2. * (x * z - y * w)],
+            [2. * (x * y - z * w), 1. - 2. * (x * x + z * z), 2. * (y * z + x * w)],
+            [2. * (x * z + y * w), 2. * (y * z - x * w), 1. - 2. * (x * x + y * y)],
+        ];
+        Transform { translation, axes, scale }
+    }
+}
+
+/// Axes X, Y and Z of UE's `FRotationMatrix` of the rotator in degrees
+fn rotator_to_axes(pitch: f64, yaw: f64, roll: f64) -> [[f64; 3]; 3] {
+    let (sp, cp) = pitch.to_radians().sin_cos();
+    let (sy, cy) = yaw.to_radians().sin_cos();
+    let (sr, cr) = roll.to_radians().sin_cos();
+    [
+        [cp * cy, cp * sy, sp],
+        [sr * sp * cy - cr * sy, sr * sp * sy + cr * cy, -sr * cp],
+        [-(cr * sp * cy + sr * sy), cy * sr - cr * sp * sy, cr * cp],
+    ]
+}
+
+/// Inverse of `rotator_to_axes` like UE's `FMatrix::Rotator`
+fn axes_to_rotator([x, y, z]: [[f64; 3]; 3]) -> (f64, f64, f64) {
+    let pitch = x[2].atan2((x[0] * x[0] + x[1] * x[1]).sqrt());
+    let yaw = x[1].atan2(x[0]);
+    let (sy, cy) = yaw.sin_cos();
+    let syaxis = [-sy, cy, 0.];
+    let roll = dot(z, syaxis).atan2(dot(y, syaxis));
+    (pitch.to_degrees(), yaw.to_degrees(), roll.to_degrees())
+}
+
+fn add(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+fn mul(a: [f64; 3], s: f64) -> [f64; 3] {
+    a.map(|c| c * s)
+}
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+fn length(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+fn normalize(a: [f64; 3]) -> [f64; 3] {
+    mul(a, 1. / length(a))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::element;
+
+    #[test]
+    fn element_round_trips_through_quaternion() {
+        for (pitch, yaw, roll) in [(0., 0., 0.), (10., 90., -20.), (-45., 170., 60.), (80., -135., 179.)] {
+            let e = element(-1000., 250.5, 12., pitch, yaw, roll);
+            let transform = Transform::from_element(&e);
+            let transform = Transform::from_quaternion(transform.translation, transform.quaternion(), transform.scale);
+            let round_tripped = transform.to_element(&e);
+            for (a, b) in [
+                (e.x, round_tripped.x), (e.y, round_tripped.y), (e.z, round_tripped.z),
+                (e.pitch, round_tripped.pitch), (e.yaw, round_tripped.yaw), (e.roll, round_tripped.roll),
+                (e.sizex, round_tripped.sizex), (e.sizey, round_tripped.sizey), (e.sizez, round_tripped.sizez),
+            ] {
+                assert!((a - b).abs() < 1e-3, "{e:?} != {round_tripped:?}");
+            }
+        }
+    }
+}
@@ -7,9 +7,12 @@ compile_error!("currently only 64bit linux and 32bit windows are supported");
 //#[cfg(all(target_os = "macos", not(target_pointer_width = "64")))]
 //compile_error!("must be compiled as 64bit on macOS (e.g. with `--target x86_64-apple-darwin`");
 
+use std::io::Write;
+use std::net::TcpStream;
 use std::sync::Once;
 use std::thread;
 use std::panic;
+use std::time::Duration;
 
 mod error;
 #[macro_use] mod log;
@@ -22,6 +25,9 @@ mod watch;
 #[cfg(windows)] pub use native::DllMain;
 
 static INIT: Once = Once::new();
+/// Time a thread gets to execute the few instructions between entering or leaving an interceptor
+/// and it being counted in `hook::active_calls`.
+const HOOK_EXIT_GRACE_PERIOD: Duration = Duration::from_millis(100);
 
 pub extern "C" fn initialize() {
     INIT.call_once(|| {
@@ -47,8 +53,10 @@ pub extern "C" fn initialize() {
             thread::spawn(|| {
                 log!("Starting initialize");
                 // on Linux we need to wait for the packer to finish
-                if cfg!(unix) {
-                    ::std::thread::sleep(::std::time::Duration::from_secs(7));
+                #[cfg(unix)]
+                if !native::wait_until_unpacked() {
+                    log!("Refunct's code isn't unpacked, rtil stays inactive");
+                    return;
                 }
                 // hook stuff
                 #[cfg(windows)]
@@ -56,9 +64,46 @@ pub extern "C" fn initialize() {
                 let hooks = native::init();
                 #[cfg(windows)]
                 native::resume_threads(handles);
+                let Some(hooks) = hooks else {
+                    log!("Refunct's layout doesn't match, rtil stays inactive");
+                    return;
+                };
                 // start threads
                 threads::start(hooks);
             });
         }
     });
 }
+
+/// Counterpart of `initialize`, called once rebo, the UI and the listener have been stopped.
+///
+/// Confirms the unload on `con` once all hooks are removed.
+pub(crate) fn deinitialize(hooks: native::Hooks, con: Option<TcpStream>) {
+    log!("deinitialize");
+    // don't let other threads execute half-restored original code
+    #[cfg(windows)]
+    let handles = native::suspend_threads();
+    hooks.disable();
+    #[cfg(windows)]
+    native::resume_threads(handles);
+    thread::spawn(move || {
+        // UE threads, including the one that called us, must leave the interceptors before we
+        // unmap them. A thread may still be on its way into an interceptor or out of it after the
+        // count dropped to 0, so it must stay at 0 for a grace period.
+        loop {
+            while hook::active_calls() > 0 {
+                thread::sleep(Duration::from_millis(1));
+            }
+            thread::sleep(HOOK_EXIT_GRACE_PERIOD);
+            if hook::active_calls() == 0 {
+                break;
+            }
+        }
+        unsafe { hooks.remove() };
+        log!("removed all hooks");
+        if let Some(mut con) = con {
+            let _ = con.write_all(&[2]);
+        }
+        native::unload_library();
+    });
+}
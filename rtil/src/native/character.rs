@@ -1,15 +1,17 @@
 use std::cell::Cell;
 use std::ffi::c_void;
-use std::mem;
+use std::mem::{self, offset_of};
 use std::sync::atomic::{AtomicPtr, Ordering};
-use hook::{ArgsRef, RawHook, IsaAbi};
+use std::sync::Once;
+use hook::{ArgsRef, RawHook, IsaAbi, TypedHook};
 use iced::mouse::Interaction;
 use crate::native::ue::{FVector, FRotator, FString, UeU64};
-use crate::native::{AMYCHARACTER_STATICCLASS, REBO_DOESNT_START_SEMAPHORE, APLAYERCONTROLLER_GETVIEWPORTSIZE, ActorWrapper, ObjectWrapper, StructValueWrapper, BoolValueWrapper, AMYCHARACTER_UNDERWATERCHANGED, UObject, UeScope, APLAYERCONTROLLER_FLUSHPRESSEDKEYS, APLAYERCONTROLLER_GETMOUSEPOSITION};
-use crate::native::reflection::UClass;
+use crate::native::{AMYCHARACTER_STATICCLASS, REBO_DOESNT_START_SEMAPHORE, APLAYERCONTROLLER_GETVIEWPORTSIZE, ActorWrapper, ObjectWrapper, StructValueWrapper, BoolValueWrapper, FHitResult, AMYCHARACTER_UNDERWATERCHANGED, UObject, UeScope, APLAYERCONTROLLER_FLUSHPRESSEDKEYS, APLAYERCONTROLLER_GETMOUSEPOSITION};
+use crate::native::reflection::{ClassWrapper, FieldOffset, UClass};
 use crate::native::uworld::CAMERA_INDEX;
 
 static CURRENT_PLAYER: AtomicPtr<AMyCharacterUE> = AtomicPtr::new(std::ptr::null_mut());
+static FLOOR_HIT_SIZE_MISMATCH: Once = Once::new();
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct AMyCharacter(*mut AMyCharacterUE);
@@ -73,16 +75,16 @@ impl AMyCharacter {
         fun()
     }
     fn root_component(&self) -> *mut USceneComponent {
-        unsafe { (*self.0).root_component }
+        unsafe { *AMYCHARACTER_ROOTCOMPONENT.field(self.0) }
     }
     pub fn controller(&self) -> *mut APlayerController {
-        unsafe { (*self.0).controller }
+        unsafe { *AMYCHARACTER_CONTROLLER.field(self.0) }
     }
     pub fn movement(&self) -> *mut UCharacterMovementComponent {
-        unsafe { (*self.0).movement }
+        unsafe { *AMYCHARACTER_MOVEMENT.field(self.0) }
     }
     fn player_state(&self) -> *mut APlayerState {
-        unsafe { (*self.controller()).player_state }
+        unsafe { *CONTROLLER_PLAYERSTATE.field(self.controller()) }
     }
 
     pub unsafe fn new(ptr: *mut AMyCharacterUE) -> AMyCharacter {
@@ -102,6 +104,11 @@ impl AMyCharacter {
         }
         AMyCharacter(current_player)
     }
+    /// The player, `None` if the character hasn't ticked yet
+    pub fn try_get_player() -> Option<AMyCharacter> {
+        let current_player = CURRENT_PLAYER.load(Ordering::SeqCst);
+        (!current_player.is_null()).then(|| AMyCharacter(current_player))
+    }
 
     pub fn location(&self) -> (f32, f32, f32) {
         let FVector { x, y, z } = unsafe { (*self.root_component()).location };
@@ -111,47 +118,98 @@ impl AMyCharacter {
         unsafe { (*self.root_component()).location = FVector { x, y, z } };
     }
     pub fn velocity(&self) -> (f32, f32, f32) {
-        let FVector { x, y, z } = unsafe { (*self.movement()).velocity };
+        let FVector { x, y, z } = unsafe { *MOVEMENT_VELOCITY.field(self.movement()) };
         (x, y, z)
     }
     pub fn set_velocity(&mut self, x: f32, y: f32, z: f32) {
-        unsafe { (*self.movement()).velocity = FVector { x, y, z } };
+        unsafe { *MOVEMENT_VELOCITY.field(self.movement()) = FVector { x, y, z } };
     }
     pub fn acceleration(&self) -> (f32, f32, f32) {
-        let FVector { x, y, z } = unsafe { (*self.movement()).acceleration };
+        let FVector { x, y, z } = unsafe { *MOVEMENT_ACCELERATION.field(self.movement()) };
         (x, y, z)
     }
     pub fn set_acceleration(&mut self, x: f32, y: f32, z: f32) {
-        unsafe { (*self.movement()).acceleration = FVector { x, y, z } };
+        unsafe { *MOVEMENT_ACCELERATION.field(self.movement()) = FVector { x, y, z } };
     }
     pub fn rotation(&self) -> (f32, f32, f32) {
-        let FRotator { pitch, yaw, roll } = unsafe { (*self.controller()).rotation };
+        let FRotator { pitch, yaw, roll } = unsafe { *CONTROLLER_CONTROLROTATION.field(self.controller()) };
         (pitch, yaw, roll)
     }
     pub fn set_rotation(&mut self, pitch: f32, yaw: f32, roll: f32) {
-        unsafe { (*self.controller()).rotation = FRotator { pitch, yaw, roll } };
+        unsafe { *CONTROLLER_CONTROLROTATION.field(self.controller()) = FRotator { pitch, yaw, roll } };
     }
 
     pub fn get_player_name(&self) -> String {
-        unsafe { (*self.player_state()).player_name.to_string_lossy() }
+        unsafe { (*PLAYERSTATE_PLAYERNAME.field::<_, FString>(self.player_state())).to_string_lossy() }
     }
     pub fn get_steamid(&self) -> u64 {
-        let ptr = unsafe { (*self.player_state()).unique_id.unique_id };
+        let ptr = unsafe { (*PLAYERSTATE_UNIQUEID.field::<_, FUniqueNetIdRepl>(self.player_state())).unique_id };
         assert!(!ptr.is_null());
         unsafe { (*ptr).steamid.get() }
     }
 
     pub fn movement_mode(&self) -> u8 {
-        unsafe { (*self.movement()).movement_mode }
+        unsafe { *MOVEMENT_MOVEMENTMODE.field(self.movement()) }
     }
     pub fn set_movement_mode(&mut self, value: u8) {
-        unsafe { (*self.movement()).movement_mode = value };
+        unsafe { *MOVEMENT_MOVEMENTMODE.field(self.movement()) = value };
     }
     pub fn max_fly_speed(&self) -> f32 {
-        unsafe { (*self.movement()).max_fly_speed }
+        unsafe { *MOVEMENT_MAXFLYSPEED.field(self.movement()) }
     }
     pub fn set_max_fly_speed(&mut self, value: f32) {
-        unsafe { (*self.movement()).max_fly_speed = value };
+        unsafe { *MOVEMENT_MAXFLYSPEED.field(self.movement()) = value };
+    }
+
+    fn movement_object(&self) -> ObjectWrapper<'_> {
+        unsafe { ObjectWrapper::new(self.movement() as *mut UObject) }
+    }
+    /// Float property of the `UCharacterMovementComponent`, e.g. `JumpZVelocity`
+    pub fn movement_property(&self, name: &str) -> f32 {
+        self.movement_object().get_field(name).unwrap::<f32>()
+    }
+    pub fn set_movement_property(&self, name: &str, value: f32) {
+        self.movement_object().get_field(name).unwrap::<&Cell<f32>>().set(value)
+    }
+    pub fn is_falling(&self) -> bool {
+        let movement = self.movement_object();
+        let fun = movement.class().find_function("IsFalling").unwrap();
+        let params = fun.create_argument_struct();
+        unsafe { fun.call(movement.as_ptr(), &params) };
+        params.get_field("ReturnValue").unwrap::<BoolValueWrapper>().get()
+    }
+    pub fn is_crouched(&self) -> bool {
+        let character = unsafe { ObjectWrapper::new(self.as_ptr() as *mut UObject) };
+        character.get_field("bIsCrouched").unwrap::<BoolValueWrapper>().get()
+    }
+    /// Start or stop crouching, which only takes effect if the character can crouch
+    pub fn set_crouched(&self, crouched: bool) {
+        let character = unsafe { ObjectWrapper::new(self.as_ptr() as *mut UObject) };
+        let fun = character.class().find_function(if crouched { "Crouch" } else { "UnCrouch" }).unwrap();
+        let params = fun.create_argument_struct();
+        params.get_field("bClientSimulation").unwrap::<BoolValueWrapper>().set(false);
+        unsafe { fun.call(character.as_ptr(), &params) };
+    }
+    /// (walkable, distance to the floor, hit of the floor if there is one) of the floor the character stands on
+    /// or was found below them at the last movement update
+    pub fn current_floor(&self) -> (bool, f32, Option<FHitResult>) {
+        let floor = self.movement_object().get_field("CurrentFloor").unwrap::<StructValueWrapper>();
+        let walkable = floor.get_field("bWalkableFloor").unwrap::<BoolValueWrapper>().get();
+        let distance = floor.get_field("FloorDist").unwrap::<f32>();
+        let hit = floor.get_field("bBlockingHit").unwrap::<BoolValueWrapper>().get().then(|| {
+            let hit = floor.get_field("HitResult");
+            if hit.prop().size() != mem::size_of::<FHitResult>() {
+                FLOOR_HIT_SIZE_MISMATCH.call_once(|| log!(
+                    "CurrentFloor.HitResult has {} bytes, but FHitResult has {}, ignoring all floor hits",
+                    hit.prop().size(), mem::size_of::<FHitResult>(),
+                ));
+                return None;
+            }
+            let hit = hit.unwrap::<StructValueWrapper>();
+            // the wrapped struct has the same layout as UE's FHitResult
+            Some(unsafe { std::ptr::read(hit.as_ptr() as *const FHitResult) })
+        }).flatten();
+        (walkable, distance, hit)
     }
 
     pub fn get_max_walk_speed() -> f32 {
@@ -196,6 +254,12 @@ impl AMyCharacter {
             fun.call(obj.as_ptr(), &params);
         }
     }
+    /// (radius, half height) of the player's collision capsule
+    pub fn capsule_size(&self) -> (f32, f32) {
+        let character = unsafe { ObjectWrapper::new(self.as_ptr() as *mut UObject) };
+        let capsule = character.get_field("CapsuleComponent").unwrap::<ObjectWrapper>();
+        (capsule.get_field("CapsuleRadius").unwrap::<f32>(), capsule.get_field("CapsuleHalfHeight").unwrap::<f32>())
+    }
     pub fn camera_mode() -> u8 {
         UeScope::with(|scope| {
             let cam = scope.get(CAMERA_INDEX.get().unwrap());
@@ -233,7 +297,70 @@ impl AMyCharacter {
     }
 }
 
+// The following raw structs are the static layouts of the UE structs.
+// Fields which are UE properties are accessed via their `FieldOffset`, which is resolved
+// via reflection in `init` and checked against these layouts.
+
+static AMYCHARACTER_ROOTCOMPONENT: FieldOffset = FieldOffset::new("RootComponent", offset_of!(AMyCharacterUE, root_component));
+static AMYCHARACTER_CONTROLLER: FieldOffset = FieldOffset::new("Controller", offset_of!(AMyCharacterUE, controller));
+static AMYCHARACTER_MOVEMENT: FieldOffset = FieldOffset::new("CharacterMovement", offset_of!(AMyCharacterUE, movement));
+static MOVEMENT_VELOCITY: FieldOffset = FieldOffset::new("Velocity", offset_of!(UCharacterMovementComponent, velocity));
+static MOVEMENT_MOVEMENTMODE: FieldOffset = FieldOffset::new("MovementMode", offset_of!(UCharacterMovementComponent, movement_mode));
+static MOVEMENT_MAXFLYSPEED: FieldOffset = FieldOffset::new("MaxFlySpeed", offset_of!(UCharacterMovementComponent, max_fly_speed));
+static MOVEMENT_ACCELERATION: FieldOffset = FieldOffset::new("Acceleration", offset_of!(UCharacterMovementComponent, acceleration));
+static CONTROLLER_PLAYERSTATE: FieldOffset = FieldOffset::new("PlayerState", offset_of!(APlayerController, player_state));
+static CONTROLLER_CONTROLROTATION: FieldOffset = FieldOffset::new("ControlRotation", offset_of!(APlayerController, rotation));
+static PLAYERSTATE_PLAYERNAME: FieldOffset = FieldOffset::new("PlayerName", offset_of!(APlayerState, player_name));
+static PLAYERSTATE_UNIQUEID: FieldOffset = FieldOffset::new("UniqueId", offset_of!(APlayerState, unique_id));
+
+/// Resolve the field offsets of the raw structs via reflection and check them against the static layouts.
+///
+/// Returns `false` if a field couldn't be found via reflection, because its static offset is unverified.
+pub(in crate::native) fn init() -> bool {
+    UeScope::with(|_scope| {
+        let character = unsafe { ClassWrapper::new(AMyCharacter::static_class() as *mut UClass) };
+        AMYCHARACTER_ROOTCOMPONENT.resolve(&character);
+        if let Some(movement) = AMYCHARACTER_MOVEMENT.resolve_object(&character) {
+            MOVEMENT_VELOCITY.resolve(&movement);
+            MOVEMENT_MOVEMENTMODE.resolve(&movement);
+            MOVEMENT_MAXFLYSPEED.resolve(&movement);
+            MOVEMENT_ACCELERATION.resolve(&movement);
+        }
+        // the property is an AController, which contains all fields we need from APlayerController
+        if let Some(controller) = AMYCHARACTER_CONTROLLER.resolve_object(&character) {
+            if let Some(player_state) = CONTROLLER_PLAYERSTATE.resolve_object(&controller) {
+                PLAYERSTATE_PLAYERNAME.resolve(&player_state);
+                PLAYERSTATE_UNIQUEID.resolve(&player_state);
+            }
+            CONTROLLER_CONTROLROTATION.resolve(&controller);
+        }
+
+        let offsets = [
+            &AMYCHARACTER_ROOTCOMPONENT, &AMYCHARACTER_CONTROLLER, &AMYCHARACTER_MOVEMENT,
+            &MOVEMENT_VELOCITY, &MOVEMENT_MOVEMENTMODE, &MOVEMENT_MAXFLYSPEED, &MOVEMENT_ACCELERATION,
+            &CONTROLLER_PLAYERSTATE, &CONTROLLER_CONTROLROTATION, &PLAYERSTATE_PLAYERNAME, &PLAYERSTATE_UNIQUEID,
+        ];
+        let mismatches: Vec<_> = offsets.iter()
+            .filter(|offset| offset.is_resolved() && !offset.matches_static_layout())
+            .map(|offset| offset.property_name())
+            .collect();
+        if !mismatches.is_empty() {
+            log!("SELF-CHECK: using the reflected offsets of {mismatches:?}, which differ from the static layouts");
+        }
+        let unresolved: Vec<_> = offsets.iter()
+            .filter(|offset| !offset.is_resolved())
+            .map(|offset| offset.property_name())
+            .collect();
+        if !unresolved.is_empty() {
+            log!("SELF-CHECK: couldn't resolve {unresolved:?} via reflection, refusing to use their static offsets");
+            return false;
+        }
+        true
+    })
+}
+
 #[repr(C)]
+#[allow(dead_code)]
 pub struct AMyCharacterUE {
     #[cfg(unix)] _pad: [u8; 0x168],
     #[cfg(windows)] _pad: [u8; 0x11c],
@@ -250,6 +377,7 @@ pub struct AMyCharacterUE {
 pub(crate) struct USceneComponent {
     #[cfg(unix)] _pad: [u8; 0x1a0],
     #[cfg(windows)] _pad: [u8; 0x140],
+    // ComponentToWorld's translation, which isn't a UE property and thus can't be resolved via reflection
     location: FVector,
 }
 
@@ -288,6 +416,7 @@ impl USceneComponent {
 }
 
 #[repr(C)]
+#[allow(dead_code)]
 pub struct UCharacterMovementComponent {
     #[cfg(unix)] _pad: [u8; 0x104],
     #[cfg(windows)] _pad: [u8; 0xb4],
@@ -304,6 +433,7 @@ pub struct UCharacterMovementComponent {
 }
 
 #[repr(C)]
+#[allow(dead_code)]
 pub struct APlayerController {
     #[cfg(unix)] _pad: [u8; 0x3a8],
     #[cfg(windows)] _pad: [u8; 0x2c8],
@@ -313,6 +443,7 @@ pub struct APlayerController {
 }
 
 #[repr(C)]
+#[allow(dead_code)]
 struct APlayerState {
     #[cfg(unix)] _pad: [u8; 0x390],
     #[cfg(windows)] _pad: [u8; 0x2bc],
@@ -344,10 +475,18 @@ pub fn tick_hook<IA: IsaAbi>(hook: &'static RawHook<IA, ()>, mut args: ArgsRef<'
     log!("Got AMyCharacter::RootComponent: {:p}", my_character.root_component());
     log!("Got AMyCharacter::Controller: {:p}", my_character.controller());
     log!("Got AMyCharacter::Movement: {:p}", my_character.movement());
-    log!("Got AMyCharacter::Movement::MovementMode: {:p}", unsafe { &(*my_character.movement()).movement_mode });
-    log!("Got AMyCharacter::Movement::Acceleration: {:p}", unsafe { &(*my_character.movement()).acceleration });
-    log!("Got AMyCharacter::Movement::MaxFlySpeed : {:p}", unsafe { &(*my_character.movement()).max_fly_speed });
+    log!("Got AMyCharacter::Movement::MovementMode: {:p}", unsafe { MOVEMENT_MOVEMENTMODE.field::<_, u8>(my_character.movement()) });
+    log!("Got AMyCharacter::Movement::Acceleration: {:p}", unsafe { MOVEMENT_ACCELERATION.field::<_, FVector>(my_character.movement()) });
+    log!("Got AMyCharacter::Movement::MaxFlySpeed : {:p}", unsafe { MOVEMENT_MAXFLYSPEED.field::<_, f32>(my_character.movement()) });
     hook.disable();
     unsafe { hook.call_original_function(args) };
     REBO_DOESNT_START_SEMAPHORE.release();
 }
+
+pub fn underwater_changed_hook<IA: IsaAbi>(hook: &TypedHook<IA, fn(*mut AMyCharacterUE, bool), ()>, this: *mut AMyCharacterUE, underwater: bool) {
+    // pawns of other players also have an `AMyCharacter`
+    if this == CURRENT_PLAYER.load(Ordering::SeqCst) {
+        crate::threads::ue::underwater_changed(underwater);
+    }
+    unsafe { hook.call_original_function((this, underwater)) };
+}
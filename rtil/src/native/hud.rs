@@ -1,6 +1,7 @@
 use std::mem;
 use std::ptr;
 use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::Once;
 use bit_field::BitField;
 use atomic_float::AtomicF32;
 
@@ -8,12 +9,13 @@ use atomic_float::AtomicF32;
 use hook::{IsaAbi, TypedHook};
 #[cfg(windows)] use winapi::ctypes::c_void;
 
-use crate::native::ue::{FLinearColor, FString, FVector, FVector2D};
-use crate::native::{AHUD_DRAWLINE, AHUD_DRAWTEXT, AHUD_DRAWTEXTURESIMPLE, AHUD_DRAWTEXTURE, AHUD_DRAWRECT, AHUD_PROJECT, AHUD_GETTEXTSIZE, REBO_DOESNT_START_SEMAPHORE, UTexture2D, UObject, ObjectWrapper, AMyCharacter};
+use crate::native::ue::{FLinearColor, FString, FVector, FVector2D, TArray};
+use crate::native::{AHUD_DRAWLINE, AHUD_DRAWTEXT, AHUD_DRAWTEXTURESIMPLE, AHUD_DRAWTEXTURE, AHUD_DRAWRECT, AHUD_PROJECT, AHUD_GETTEXTSIZE, REBO_DOESNT_START_SEMAPHORE, UTexture2D, UObject, ObjectWrapper, AMyCharacter, ArrayPropertyWrapper, ArrayWrapper, DynamicValue};
 use crate::native::texture::UTexture2DUE;
 use crate::threads::ue;
 
 static AMYHUD: AtomicPtr<AMyHud> = AtomicPtr::new(ptr::null_mut());
+static TRIANGLE_SIZE_MISMATCH: Once = Once::new();
 
 macro_rules! get_amyhud {
     ($fnname:literal) => {{
@@ -27,6 +29,45 @@ macro_rules! get_amyhud {
     }}
 }
 
+/// Screen-space line for [`AMyHud::draw_lines`]
+#[derive(Debug, Clone, Copy)]
+pub struct HudLine {
+    pub start: (f32, f32),
+    pub end: (f32, f32),
+    pub color: (f32, f32, f32, f32),
+    pub thickness: f32,
+}
+
+#[repr(C)]
+struct FCanvasUVTri {
+    v0_pos: FVector2D,
+    v0_uv: FVector2D,
+    v0_color: FLinearColor,
+    v1_pos: FVector2D,
+    v1_uv: FVector2D,
+    v1_color: FLinearColor,
+    v2_pos: FVector2D,
+    v2_uv: FVector2D,
+    v2_color: FLinearColor,
+}
+
+impl HudLine {
+    /// The line as rectangle of two triangles
+    fn triangles(&self) -> [FCanvasUVTri; 2] {
+        let ((sx, sy), (ex, ey)) = (self.start, self.end);
+        let length = ((ex - sx).powi(2) + (ey - sy).powi(2)).sqrt().max(f32::EPSILON);
+        // half the thickness perpendicular to the line
+        let (nx, ny) = (-(ey - sy) / length * self.thickness / 2., (ex - sx) / length * self.thickness / 2.);
+        let corners = [(sx + nx, sy + ny), (sx - nx, sy - ny), (ex - nx, ey - ny), (ex + nx, ey + ny)];
+        let tri = |a: usize, b: usize, c: usize| {
+            let vertex = |i: usize| (FVector2D { x: corners[i].0, y: corners[i].1 }, FVector2D { x: 0., y: 0. }, FLinearColor::from(self.color));
+            let ((v0_pos, v0_uv, v0_color), (v1_pos, v1_uv, v1_color), (v2_pos, v2_uv, v2_color)) = (vertex(a), vertex(b), vertex(c));
+            FCanvasUVTri { v0_pos, v0_uv, v0_color, v1_pos, v1_uv, v1_color, v2_pos, v2_uv, v2_color }
+        };
+        [tri(0, 1, 2), tri(0, 2, 3)]
+    }
+}
+
 pub enum AHudUE {}
 pub enum UMaterialInterfaceUE {}
 #[repr(C)]
@@ -46,6 +87,53 @@ impl AMyHud {
         fun(get_amyhud!("draw_line"), startx, starty, endx, endy, color.into(), thickness)
     }
 
+    /// Draw all lines with a single `UCanvas::K2_DrawTriangle` call instead of one `draw_line` call each,
+    /// falling back to the latter if the canvas or its triangles don't match
+    pub fn draw_lines(lines: &[HudLine]) {
+        if lines.is_empty() {
+            return;
+        }
+        let draw_each = || {
+            for line in lines {
+                AMyHud::draw_line(line.start.0, line.start.1, line.end.0, line.end.1, line.color, line.thickness);
+            }
+        };
+        let hud = unsafe { ObjectWrapper::new(get_amyhud!("draw_lines") as *mut UObject) };
+        let canvas = hud.get_field("Canvas").unwrap_object();
+        let Some((canvas, fun)) = canvas.and_then(|canvas| {
+            let fun = canvas.class().find_function("K2_DrawTriangle")?;
+            Some((canvas, fun))
+        }) else {
+            draw_each();
+            return;
+        };
+        let params = fun.create_argument_struct();
+        let triangles = params.get_field("Triangles");
+        let triangle_size = triangles.prop().upcast::<ArrayPropertyWrapper>().inner().size();
+        if triangle_size != mem::size_of::<FCanvasUVTri>() {
+            TRIANGLE_SIZE_MISMATCH.call_once(|| log!(
+                "K2_DrawTriangle's triangles have {} bytes, but FCanvasUVTri has {}, drawing lines one by one",
+                triangle_size, mem::size_of::<FCanvasUVTri>(),
+            ));
+            draw_each();
+            return;
+        }
+        let array_ptr = triangles.unwrap::<ArrayWrapper<DynamicValue>>().as_ptr() as *mut TArray<FCanvasUVTri>;
+        let mut array = TArray::with_capacity(lines.len() * 2);
+        for line in lines {
+            for triangle in line.triangles() {
+                array.push(triangle);
+            }
+        }
+        unsafe {
+            // the zeroed argument struct doesn't own an allocation we'd leak
+            ptr::write(array_ptr, array);
+            fun.call(canvas.as_ptr(), &params);
+            // the array is passed by value, so the canvas copied it and we still own ours
+            drop(ptr::read(array_ptr));
+        }
+    }
+
     pub fn draw_text<S: Into<FString>, C:Into<FLinearColor>>(text: S, color: C, x: f32, y: f32, scale: f32, scale_position: bool) {
         unsafe {
             let fun: extern_fn!(fn(
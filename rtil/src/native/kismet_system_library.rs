@@ -1,51 +1,22 @@
+use std::cell::Cell;
 use std::ffi::c_void;
 use std::marker::PhantomData;
-use std::ptr;
+use std::{mem, ptr};
+use std::sync::{Once, OnceLock};
 use std::sync::atomic::Ordering;
-use crate::native::{AActor, AMyCharacter, GlobalObjectArrayWrapper, ObjectWrapper, StructValueWrapper, UObject};
+use crate::native::{AActor, AMyCharacter, BoolValueWrapper, GlobalObjectArrayWrapper, ObjectIndex, ObjectWrapper, ObjectWrapperType, StructValueWrapper, UeScope, UObject};
 use crate::native::ue::{FLinearColor, FName, FVector, TArray};
 use crate::native::{UKISMETSYSTEMLIBRARY_LINETRACESINGLE, FROTATOR_VECTOR};
 
 pub struct KismetSystemLibrary;
 
+static KISMET_SYSTEM_LIBRARY_INDEX: OnceLock<ObjectIndex<ObjectWrapperType>> = OnceLock::new();
+/// Logs the layout mismatch only once, as capsules are traced every frame while the trajectory is drawn
+static OUT_HIT_SIZE_MISMATCH: Once = Once::new();
+
 impl KismetSystemLibrary {
     pub fn line_trace_single(player: AMyCharacter) -> *mut AActor {
         unsafe {
-            let fun: extern "C" fn(
-                world_context_object: *mut UObject,
-                start: FVector,
-                end: FVector,
-                trace_channel: i32,
-                trace_complex: bool,
-                actors_to_ignore: *mut TArray<*mut AActor>,
-                draw_debug_type: EDrawDebugTraceType,
-                out_hit: *mut FHitResult,
-                ignore_self: bool,
-                trace_color: FLinearColor,
-                trace_hit_color: FLinearColor,
-                draw_time: f32
-            ) -> bool = std::mem::transmute(UKISMETSYSTEMLIBRARY_LINETRACESINGLE.load(Ordering::SeqCst));
-
-            let mut hit_result = FHitResult {
-                bitfield: 0,
-                time: 0.0,
-                distance: 0.0,
-                location: Default::default(),
-                impact_point: Default::default(),
-                normal: Default::default(),
-                impact_normal: Default::default(),
-                trace_start: Default::default(),
-                trace_end: Default::default(),
-                penetration_depth: 0.0,
-                item: 0,
-                phys_material: Default::default(),
-                actor: Default::default(),
-                component: Default::default(),
-                bone_name: FName::NAME_None,
-                face_index: 0,
-            };
-
-
             let character = ObjectWrapper::new(player.as_ptr() as *mut UObject);
             let camera: ObjectWrapper = character.get_field("Controller").field("PlayerCameraManager").unwrap();
             let get_camera_location = camera.class().find_function("GetCameraLocation").unwrap();
@@ -65,34 +36,109 @@ impl KismetSystemLibrary {
                 y: loc.get_field("Y").unwrap(),
                 z: loc.get_field("Z").unwrap(),
             };
+            let end = FVector { x: direction.x * 100000. + location.x, y: direction.y * 100000. + location.y, z: direction.z * 100000. + location.z };
+
+            match Self::line_trace(player, location, end, 0, EDrawDebugTraceType::ForDuration) {
+                Some(hit_result) => hit_result.actor(),
+                None => ptr::null_mut(),
+            }
+        }
+    }
+
+    /// Trace along the line from `start` to `end`, ignoring the player.
+    /// `channel` is the `ETraceTypeQuery`, where 0 is `Visibility` and 1 is `Camera`.
+    pub fn line_trace(player: AMyCharacter, start: FVector, end: FVector, channel: u8, draw_debug_type: EDrawDebugTraceType) -> Option<FHitResult> {
+        unsafe {
+            let fun: extern "C" fn(
+                world_context_object: *mut UObject,
+                start: FVector,
+                end: FVector,
+                trace_channel: i32,
+                trace_complex: bool,
+                actors_to_ignore: *mut TArray<*mut AActor>,
+                draw_debug_type: EDrawDebugTraceType,
+                out_hit: *mut FHitResult,
+                ignore_self: bool,
+                trace_color: FLinearColor,
+                trace_hit_color: FLinearColor,
+                draw_time: f32
+            ) -> bool = std::mem::transmute(UKISMETSYSTEMLIBRARY_LINETRACESINGLE.load(Ordering::SeqCst));
 
+            let mut hit_result = FHitResult::default();
             let hit = fun(
                 player.as_ptr() as *mut UObject,
-                location,
-                FVector { x: direction.x * 100000. + location.x, y: direction.y * 100000. + location.y, z: direction.z * 100000. + location.z },
-                0,
+                start,
+                end,
+                channel as i32,
                 false,
                 &mut TArray::new(),
-                EDrawDebugTraceType::ForDuration,
+                draw_debug_type,
                 &mut hit_result,
                 true,
                 FLinearColor { red: 1., green: 0., blue: 0., alpha: 1. },
                 FLinearColor { red: 1., green: 0., blue: 0., alpha: 1. },
                 10.,
             );
+            hit.then_some(hit_result)
+        }
+    }
+
+    /// Sweep a capsule from `start` to `end`, ignoring the player.
+    /// `half_height` includes the radius like in UE's `UCapsuleComponent`.
+    pub fn capsule_trace(player: AMyCharacter, start: FVector, end: FVector, radius: f32, half_height: f32, channel: u8) -> Option<FHitResult> {
+        UeScope::with(|scope| {
+            let index = match KISMET_SYSTEM_LIBRARY_INDEX.get() {
+                Some(index) => index,
+                None => {
+                    let Some(object) = scope.iter_global_object_array()
+                        .map(|item| item.object())
+                        .find(|object| object.name() == "Default__KismetSystemLibrary")
+                    else {
+                        log!("KismetSystemLibrary CDO not found, can't trace a capsule");
+                        return None;
+                    };
+                    KISMET_SYSTEM_LIBRARY_INDEX.get_or_init(|| scope.object_index(&object))
+                }
+            };
+            let kismet = scope.get(index);
+            let Some(fun) = kismet.class().find_function("CapsuleTraceSingle_NEW") else {
+                log!("KismetSystemLibrary::CapsuleTraceSingle_NEW not found, can't trace a capsule");
+                return None;
+            };
+            let params = fun.create_argument_struct();
+            params.get_field("WorldContextObject").set_object(&unsafe { ObjectWrapper::new(player.as_ptr() as *mut UObject) });
+            set_vector(params.get_field("Start").unwrap(), start);
+            set_vector(params.get_field("End").unwrap(), end);
+            params.get_field("Radius").unwrap::<&Cell<f32>>().set(radius);
+            params.get_field("HalfHeight").unwrap::<&Cell<f32>>().set(half_height);
+            params.get_field("TraceChannel").unwrap::<&Cell<u8>>().set(channel);
+            params.get_field("bIgnoreSelf").unwrap::<BoolValueWrapper>().set(true);
+            unsafe { fun.call(kismet.as_ptr(), &params) };
 
-            if !hit {
-                ptr::null_mut()
-            } else {
-                let array = GlobalObjectArrayWrapper::get();
-                let item = array.object_array().get(hit_result.actor.object_index);
-                assert_eq!(item.serial_number(), hit_result.actor.object_serial_number);
-                item.object().as_ptr() as *mut AActor
+            if !params.get_field("ReturnValue").unwrap::<BoolValueWrapper>().get() {
+                return None;
             }
-        }
+            let out_hit = params.get_field("OutHit");
+            if out_hit.prop().size() != mem::size_of::<FHitResult>() {
+                OUT_HIT_SIZE_MISMATCH.call_once(|| log!(
+                    "OutHit of CapsuleTraceSingle_NEW has {} bytes, but FHitResult has {}, ignoring all capsule trace hits",
+                    out_hit.prop().size(), mem::size_of::<FHitResult>(),
+                ));
+                return None;
+            }
+            let out_hit = out_hit.unwrap::<StructValueWrapper>();
+            // FHitResult only contains POD, so it can be copied out of the parameter struct
+            Some(unsafe { ptr::read(out_hit.as_ptr() as *const FHitResult) })
+        })
     }
 }
 
+fn set_vector(vector: StructValueWrapper, value: FVector) {
+    vector.get_field("X").unwrap::<&Cell<f32>>().set(value.x);
+    vector.get_field("Y").unwrap::<&Cell<f32>>().set(value.y);
+    vector.get_field("Z").unwrap::<&Cell<f32>>().set(value.z);
+}
+
 #[repr(C)]
 pub struct FHitResult {
     pub bitfield: u8,
@@ -113,6 +159,39 @@ pub struct FHitResult {
     pub face_index: i32,
 }
 
+impl Default for FHitResult {
+    fn default() -> Self {
+        FHitResult {
+            bitfield: 0,
+            time: 0.0,
+            distance: 0.0,
+            location: Default::default(),
+            impact_point: Default::default(),
+            normal: Default::default(),
+            impact_normal: Default::default(),
+            trace_start: Default::default(),
+            trace_end: Default::default(),
+            penetration_depth: 0.0,
+            item: 0,
+            phys_material: Default::default(),
+            actor: Default::default(),
+            component: Default::default(),
+            bone_name: FName::NAME_None,
+            face_index: 0,
+        }
+    }
+}
+impl FHitResult {
+    /// The hit actor, or null if it was destroyed in the meantime
+    pub fn actor(&self) -> *mut AActor {
+        let array = unsafe { GlobalObjectArrayWrapper::get() };
+        match array.object_array().try_get(self.actor.object_index) {
+            Some(item) if item.serial_number() == self.actor.object_serial_number => item.object().as_ptr() as *mut AActor,
+            _ => ptr::null_mut(),
+        }
+    }
+}
+
 #[repr(C)]
 pub struct TWeakObjectPtr<T> {
     object_index: i32,
@@ -128,7 +207,7 @@ impl<T> Default for TWeakObjectPtr<T> {
 
 #[repr(i32)]
 #[allow(unused)]
-enum EDrawDebugTraceType {
+pub enum EDrawDebugTraceType {
     None,
     ForOneFrame,
     ForDuration,
@@ -1,6 +1,7 @@
-use std::env;
+use std::{env, fs, thread};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
 
 // Shoutout to https://github.com/geofft/redhook/blob/master/src/ld_preload.rs#L18
 // Rust doesn't directly expose __attribute__((constructor)), but this
@@ -39,6 +40,95 @@ pub static INITIALIZE_CTOR: extern "C" fn() = crate::initialize;
 //     (elf_base_address as isize + base_offset) as usize
 // }
 
+/// Symbol whose code is used to check if the packer has finished unpacking.
+const READINESS_PROBE_SYMBOL: &str = "^UObject::ProcessEvent(UFunction*, void*)";
+const READINESS_PROBE_LEN: usize = 64;
+const READINESS_PROBE_INTERVAL: Duration = Duration::from_millis(100);
+/// Number of consecutive polls with unchanged bytes after which we consider the code stable.
+const READINESS_PROBE_STABLE_POLLS: u32 = 20;
+const READINESS_PROBE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Instructions an x86_64 function of the game can start with:
+/// `endbr64`, `push rbp`, `push rbx`, `push r12`-`push r15` and `sub rsp, imm`
+const FUNCTION_PROLOGUES: &[&[u8]] = &[
+    &[0xf3, 0x0f, 0x1e, 0xfa],
+    &[0x55],
+    &[0x53],
+    &[0x41, 0x54], &[0x41, 0x55], &[0x41, 0x56], &[0x41, 0x57],
+    &[0x48, 0x83, 0xec], &[0x48, 0x81, 0xec],
+];
+
+/// Wait for the packer to finish unpacking the executable.
+///
+/// Refunct's code is only usable once the packer has mapped and written all code pages.
+/// We poll the code of a known symbol until it either matches the bytes in the ELF on disk,
+/// or until it is mapped executable, starts with a function prologue and hasn't changed for a while.
+/// Returns `false` if neither happens within the timeout, in which case we must not hook anything.
+pub fn wait_until_unpacked() -> bool {
+    let exe = env::current_exe().unwrap();
+    let addr = dynsym::iter(&exe).into_iter()
+        .find(|(name, _)| name.starts_with(READINESS_PROBE_SYMBOL.strip_prefix('^').unwrap()))
+        .map(|(_, addr)| addr);
+    let Some(addr) = addr else {
+        log!("readiness probe symbol {:?} not found", READINESS_PROBE_SYMBOL);
+        return false;
+    };
+    let expected = dynsym::bytes_at(&exe, addr, READINESS_PROBE_LEN);
+    log!("waiting for code at {:#x} to be unpacked (on-disk bytes available: {})", addr, expected.is_some());
+
+    let start = Instant::now();
+    let mut last = None;
+    let mut stable_polls = 0;
+    loop {
+        if code_mapping_is_executable(addr, READINESS_PROBE_LEN) {
+            // SAFETY: the range is mapped readable according to /proc/self/maps
+            let current = unsafe { std::slice::from_raw_parts(addr as *const u8, READINESS_PROBE_LEN) }.to_vec();
+            if expected.as_ref() == Some(&current) {
+                log!("code matches on-disk ELF after {:?}", start.elapsed());
+                return true;
+            }
+            let has_prologue = FUNCTION_PROLOGUES.iter().any(|prologue| current.starts_with(prologue));
+            if last.as_ref() == Some(&current) && has_prologue {
+                stable_polls += 1;
+            } else {
+                stable_polls = 0;
+            }
+            if stable_polls >= READINESS_PROBE_STABLE_POLLS {
+                log!("code is mapped, starts with a function prologue and is stable after {:?}", start.elapsed());
+                return true;
+            }
+            last = Some(current);
+        } else {
+            stable_polls = 0;
+            last = None;
+        }
+        if start.elapsed() >= READINESS_PROBE_TIMEOUT {
+            log!("readiness probe timed out after {:?}, not initializing", READINESS_PROBE_TIMEOUT);
+            return false;
+        }
+        thread::sleep(READINESS_PROBE_INTERVAL);
+    }
+}
+
+/// We can't `dlclose` ourselves: glibc never unloads a library that registered thread-local
+/// destructors, which rust's std does. Instead, rtil stays mapped but inert, and the tool injects
+/// newer builds under a new filename. This leaks the code and statics of every unloaded copy,
+/// which is why the tool only allows a single reload per game process.
+pub fn unload_library() {
+    log!("rtil stays mapped, but is inactive now");
+}
+
+/// Check in `/proc/self/maps` if the given range is mapped readable and executable.
+fn code_mapping_is_executable(addr: usize, len: usize) -> bool {
+    let Ok(maps) = fs::read_to_string("/proc/self/maps") else { return false };
+    maps.lines().any(|line| {
+        let mut parts = line.split_whitespace();
+        let (Some(range), Some(perms)) = (parts.next(), parts.next()) else { return false };
+        let Some((start, end)) = range.split_once('-') else { return false };
+        let (Ok(start), Ok(end)) = (usize::from_str_radix(start, 16), usize::from_str_radix(end, 16)) else { return false };
+        start <= addr && addr + len <= end && perms.starts_with("r-x")
+    })
+}
+
 macro_rules! find {
     ($($name:ident, $symbol:expr,)*) => {
         $(
@@ -2,8 +2,9 @@ use std::cell::Cell;
 use std::fmt::{Formatter, Pointer};
 use std::ops::Deref;
 use std::sync::Mutex;
-use crate::native::{ArrayWrapper, ObjectIndex, StructValueWrapper, UeObjectWrapperType, UeScope, UObject, ObjectWrapper};
+use crate::native::{ArrayWrapper, BoolValueWrapper, DynamicValue, ObjectIndex, ObjectPropertyWrapper, StructPropertyWrapper, StructValueWrapper, UeObjectWrapperType, UeScope, UObject, ObjectWrapper};
 use crate::native::reflection::{AActor, ActorWrapper, UeObjectWrapper};
+use crate::native::ue::TArray;
 
 pub static LEVELS: Mutex<Vec<Level>> = Mutex::new(Vec::new());
 
@@ -55,6 +56,10 @@ impl<'a> LevelWrapper<'a> {
     pub fn level_index(&self) -> usize {
         self.base.get_field("LevelIndex").unwrap::<i32>().try_into().unwrap()
     }
+    /// The level root rises once the game's level reaches this index
+    pub fn set_level_index(&self, level_index: i32) {
+        self.base.get_field("LevelIndex").unwrap::<&Cell<i32>>().set(level_index)
+    }
     pub fn source_location(&self) -> (f32, f32, f32) {
         let loc = self.base.get_field("SourcePosition").unwrap::<StructValueWrapper>();
         (loc.get_field("X").unwrap(), loc.get_field("Y").unwrap(), loc.get_field("Z").unwrap())
@@ -65,6 +70,16 @@ impl<'a> LevelWrapper<'a> {
         loc.get_field("Y").unwrap::<&Cell<f32>>().set(y);
         loc.get_field("Z").unwrap::<&Cell<f32>>().set(z);
     }
+    /// The location the level root rises to from its `SourcePosition`
+    pub fn target_location(&self) -> Option<(f32, f32, f32)> {
+        let field = blueprint_field(&self.base, LEVEL_TARGET, "StructProperty")?;
+        if field.prop().upcast::<StructPropertyWrapper>().struct_().name() != "Vector" {
+            log!("{}.{LEVEL_TARGET} isn't a Vector", self.base.class().name());
+            return None;
+        }
+        let loc = field.unwrap::<StructValueWrapper>();
+        Some((loc.get_field("X").unwrap(), loc.get_field("Y").unwrap(), loc.get_field("Z").unwrap()))
+    }
     pub fn platforms(&self) -> impl Iterator<Item = PlatformWrapper<'a>> + '_ {
         self.base.get_field("FertileLands").unwrap::<ArrayWrapper<'_, _>>()
             .into_iter()
@@ -89,12 +104,59 @@ impl<'a> LevelWrapper<'a> {
         let array = self.base.get_field("Buttons").unwrap::<ArrayWrapper<'_, _>>();
         array.get(index)
     }
+    pub fn push_platform(&self, platform: &PlatformWrapper) {
+        self.push_element("FertileLands", platform);
+    }
+    pub fn truncate_platforms(&self, len: usize) {
+        self.truncate_elements("FertileLands", len);
+    }
+    pub fn push_cube(&self, cube: &CubeWrapper) {
+        self.push_element("Collectibles", cube);
+    }
+    pub fn truncate_cubes(&self, len: usize) {
+        self.truncate_elements("Collectibles", len);
+    }
+    pub fn push_button(&self, button: &ButtonWrapper) {
+        self.push_element("Buttons", button);
+    }
+    pub fn truncate_buttons(&self, len: usize) {
+        self.truncate_elements("Buttons", len);
+    }
+    fn element_array(&self, field: &str) -> *mut TArray<*mut AActor> {
+        let array = self.base.get_field(field).unwrap::<ArrayWrapper<'_, ObjectWrapper>>();
+        array.as_ptr() as *mut TArray<*mut AActor>
+    }
+    fn push_element(&self, field: &str, element: &ActorWrapper) {
+        let array = unsafe { &mut *self.element_array(field) };
+        if array.len() == array.capacity() {
+            // the array is owned by UE, so we can only reallocate it via FMemory
+            let mut new_array = TArray::with_capacity((array.capacity() * 2).max(4));
+            for &ptr in &*array {
+                new_array.push(ptr);
+            }
+            // frees the old allocation
+            *array = new_array;
+        }
+        array.push(element.as_ptr());
+    }
+    fn truncate_elements(&self, field: &str, len: usize) {
+        let array = unsafe { &mut *self.element_array(field) };
+        if array.len() > len {
+            array.len = len.try_into().unwrap();
+        }
+    }
     pub fn speed(&self) -> f32 {
         self.base.get_field("Speed").unwrap()
     }
     pub fn set_speed(&self, speed: f32) {
         self.base.get_field("Speed").unwrap::<&Cell<f32>>().set(speed)
     }
+    pub fn risen(&self) -> Option<bool> {
+        bool_field(&self.base, LEVEL_RISEN).map(|field| field.get())
+    }
+    pub fn set_risen(&self, risen: bool) -> bool {
+        bool_field(&self.base, LEVEL_RISEN).map(|field| field.set(risen)).is_some()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -131,6 +193,9 @@ impl<'a> PlatformWrapper<'a> {
         assert_eq!(base.class().name(), "BP_IslandChunk_C");
         PlatformWrapper { base }
     }
+    pub fn pressed(&self) -> Option<bool> {
+        bool_field(&self.base, PRESSED).map(|field| field.get())
+    }
 }
 #[derive(Debug, Clone)]
 pub struct CubeWrapper<'a> {
@@ -166,6 +231,9 @@ impl<'a> CubeWrapper<'a> {
         assert_eq!(cube.class().name(), "BP_PowerCore_C");
         CubeWrapper { base: cube }
     }
+    pub fn collected(&self) -> Option<bool> {
+        bool_field(&self.base, CUBE_COLLECTED).map(|field| field.get())
+    }
 }
 #[derive(Debug, Clone)]
 pub struct ButtonWrapper<'a> {
@@ -201,6 +269,9 @@ impl<'a> ButtonWrapper<'a> {
         assert_eq!(button.class().name(), "BP_Button_C");
         ButtonWrapper { base: button }
     }
+    pub fn pressed(&self) -> Option<bool> {
+        bool_field(&self.base, PRESSED).map(|field| field.get())
+    }
 }
 #[derive(Debug, Clone)]
 pub struct LiftWrapper<'a> {
@@ -236,6 +307,18 @@ impl<'a> LiftWrapper<'a> {
         assert_eq!(lift.class().name(), "BP_Lift_C");
         LiftWrapper { base: lift }
     }
+    pub fn distance(&self) -> Option<f32> {
+        float_field(&self.base, LIFT_DISTANCE).map(Cell::get)
+    }
+    pub fn set_distance(&self, distance: f32) -> bool {
+        float_field(&self.base, LIFT_DISTANCE).map(|field| field.set(distance)).is_some()
+    }
+    pub fn speed(&self) -> Option<f32> {
+        float_field(&self.base, LIFT_SPEED).map(Cell::get)
+    }
+    pub fn set_speed(&self, speed: f32) -> bool {
+        float_field(&self.base, LIFT_SPEED).map(|field| field.set(speed)).is_some()
+    }
 }
 #[derive(Debug, Clone)]
 pub struct PipeWrapper<'a> {
@@ -271,6 +354,22 @@ impl<'a> PipeWrapper<'a> {
         assert_eq!(pipe.class().name(), "BP_TravelPipe_C");
         PipeWrapper { base: pipe }
     }
+    /// The pipe the player exits from
+    fn exit_field(&self) -> Option<DynamicValue<'a>> {
+        let field = blueprint_field(&self.base, PIPE_EXIT, "ObjectProperty")?;
+        let class = field.prop().upcast::<ObjectPropertyWrapper>().property_class();
+        if !class.extends_from(PipeWrapper::CLASS_NAME) {
+            log!("{}.{PIPE_EXIT} references a {} instead of a pipe", self.base.class().name(), class.name());
+            return None;
+        }
+        Some(field)
+    }
+    pub fn exit(&self) -> Option<PipeWrapper<'a>> {
+        self.exit_field()?.unwrap_object().map(|exit| exit.upcast())
+    }
+    pub fn set_exit(&self, exit: &PipeWrapper<'a>) -> bool {
+        self.exit_field().map(|field| field.set_object(exit)).is_some()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -307,9 +406,60 @@ impl<'a> SpringpadWrapper<'a> {
         assert_eq!(springpad.class().name(), "BP_Jumppad_C");
         SpringpadWrapper { base: springpad }
     }
+    pub fn launch_velocity(&self) -> Option<f32> {
+        float_field(&self.base, SPRINGPAD_VELOCITY).map(Cell::get)
+    }
+    pub fn set_launch_velocity(&self, velocity: f32) -> bool {
+        float_field(&self.base, SPRINGPAD_VELOCITY).map(|field| field.set(velocity)).is_some()
+    }
 }
 
-#[derive(Debug, Copy, Clone, rebo::ExternalType)]
+// Blueprint properties of the map elements.
+// The blueprint classes aren't part of the SDK in this repository and the names below still need to be
+// confirmed with a `Tas::dump_sdk` dump of the respective class. Until then, `init` logs a SELF-CHECK line
+// for each of them, and accessing a missing one only logs and yields `None`.
+/// `BP_Lift_C`, unconfirmed
+const LIFT_DISTANCE: &str = "Distance";
+/// `BP_Lift_C`, unconfirmed
+const LIFT_SPEED: &str = "Speed";
+/// `BP_Jumppad_C`, unconfirmed
+const SPRINGPAD_VELOCITY: &str = "Velocity";
+/// `BP_TravelPipe_C`, referencing the pipe the player exits from, unconfirmed
+const PIPE_EXIT: &str = "TargetPipe";
+/// `BP_IslandChunk_C` and `BP_Button_C`, unconfirmed
+const PRESSED: &str = "Pressed";
+/// `BP_PowerCore_C`, unconfirmed
+const CUBE_COLLECTED: &str = "Collected";
+/// `BP_LevelRoot_C`, set once the level root reached its final height, unconfirmed
+const LEVEL_RISEN: &str = "Risen";
+/// `BP_LevelRoot_C`, counterpart of `SourcePosition`, unconfirmed.
+/// If it's wrong, all `Tas::cluster_*` functions fail with `UnknownTargetLocation`.
+const LEVEL_TARGET: &str = "TargetPosition";
+
+/// The property `name` of the actor if it has the expected kind, `None` and logged otherwise.
+///
+/// The blueprint classes aren't part of the SDK we compile against, so a property changed by a game update
+/// only disables reading and writing it instead of touching a different property.
+fn blueprint_field<'a>(actor: &ActorWrapper<'a>, name: &str, kind: &str) -> Option<DynamicValue<'a>> {
+    let Some(field) = actor.try_get_field(name) else {
+        log!("{} has no property {name}", actor.class().name());
+        return None;
+    };
+    let actual = field.prop().property_kind();
+    if actual != kind {
+        log!("{}.{name} is a {actual} instead of a {kind}", actor.class().name());
+        return None;
+    }
+    Some(field)
+}
+fn float_field<'a>(actor: &ActorWrapper<'a>, name: &str) -> Option<&'a Cell<f32>> {
+    Some(blueprint_field(actor, name, "FloatProperty")?.unwrap::<&Cell<f32>>())
+}
+fn bool_field<'a>(actor: &ActorWrapper<'a>, name: &str) -> Option<BoolValueWrapper<'a>> {
+    Some(blueprint_field(actor, name, "BoolProperty")?.unwrap::<BoolValueWrapper>())
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, rebo::ExternalType)]
 pub enum ElementType {
     Platform,
     Cube,
@@ -319,7 +469,7 @@ pub enum ElementType {
     Springpad,
 }
 
-#[derive(Debug, Clone, Copy, rebo::ExternalType)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, rebo::ExternalType)]
 pub struct ElementIndex {
     pub cluster_index: usize,
     pub element_type: ElementType,
@@ -455,5 +605,45 @@ pub fn init() {
                 (x as i32, y as i32, z as i32)
             });
         }
+
+        check_blueprint_properties(scope, &levels);
     })
 }
+
+/// Log for every blueprint property we access whether the game has it with the expected kind.
+fn check_blueprint_properties(scope: &UeScope, levels: &[Level]) {
+    fn check(actor: &ActorWrapper<'_>, properties: &[(&str, &str)]) {
+        for &(name, kind) in properties {
+            // logs if the property is missing or of a different kind
+            if blueprint_field(actor, name, kind).is_some() {
+                log!("SELF-CHECK: {}.{name} is a {kind}", actor.class().name());
+            }
+        }
+    }
+    if let Some(lift) = levels.iter().find_map(|level| level.lifts.first()) {
+        check(&scope.get(lift), &[(LIFT_DISTANCE, "FloatProperty"), (LIFT_SPEED, "FloatProperty")]);
+    }
+    if let Some(pipe) = levels.iter().find_map(|level| level.pipes.first()) {
+        check(&scope.get(pipe), &[(PIPE_EXIT, "ObjectProperty")]);
+    }
+    if let Some(springpad) = levels.iter().find_map(|level| level.springpads.first()) {
+        check(&scope.get(springpad), &[(SPRINGPAD_VELOCITY, "FloatProperty")]);
+    }
+    if let Some(platform) = levels.iter().find_map(|level| level.platforms.first()) {
+        check(&scope.get(platform), &[(PRESSED, "BoolProperty")]);
+    }
+    if let Some(button) = levels.iter().find_map(|level| level.buttons.first()) {
+        check(&scope.get(button), &[(PRESSED, "BoolProperty")]);
+    }
+    if let Some(cube) = levels.iter().find_map(|level| level.cubes.first()) {
+        check(&scope.get(cube), &[(CUBE_COLLECTED, "BoolProperty")]);
+    }
+    if let Some(level) = levels.first() {
+        let level = scope.get(level.level);
+        check(&level, &[(LEVEL_RISEN, "BoolProperty")]);
+        // logs if it's missing or not a Vector
+        if level.target_location().is_some() {
+            log!("SELF-CHECK: {}.{LEVEL_TARGET} is a Vector", level.class().name());
+        }
+    }
+}
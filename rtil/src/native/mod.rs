@@ -38,7 +38,9 @@ mod map_editor;
 mod kismet_system_library;
 mod engine;
 mod font;
+mod process_event;
 
+use std::ffi::c_void;
 use std::sync::atomic::Ordering;
 use hook::{RawHook, TypedHook};
 use crate::native::character::AMyCharacterUE;
@@ -47,8 +49,8 @@ use crate::semaphore::Semaphore;
 #[cfg(unix)] use self::linux::*;
 #[cfg(windows)] use self::windows::*;
 
-#[cfg(unix)] pub use self::linux::INITIALIZE_CTOR;
-#[cfg(windows)] pub use self::windows::{DllMain, suspend_threads, resume_threads};
+#[cfg(unix)] pub use self::linux::{INITIALIZE_CTOR, wait_until_unpacked, unload_library};
+#[cfg(windows)] pub use self::windows::{DllMain, suspend_threads, resume_threads, unload_library};
 pub use self::character::AMyCharacter;
 pub use self::slateapp::{
     FSlateApplication,
@@ -56,7 +58,7 @@ pub use self::slateapp::{
 };
 pub use self::app::FApp;
 pub use self::memory::FMemory;
-pub use self::hud::{AMyHud, EBlendMode};
+pub use self::hud::{AMyHud, EBlendMode, HudLine};
 pub use self::uworld::{UWorld, UGameplayStatics, TimeOfDay};
 pub use self::level_state::LevelState;
 pub use self::platform_misc::FPlatformMisc;
@@ -64,8 +66,9 @@ pub use self::texture::{UTexture2D, EPixelFormat};
 pub use self::gameinstance::UMyGameInstance;
 pub use self::reflection::*;
 pub use self::map_editor::*;
-pub use self::kismet_system_library::KismetSystemLibrary;
+pub use self::kismet_system_library::{KismetSystemLibrary, FHitResult, EDrawDebugTraceType};
 pub use self::engine::{UEngine, FViewport, UWidgetBlueprintLibrary};
+pub use self::process_event::{ProcessEventFilter, Overlap, add_process_event_filter, clear_process_event_filters};
 
 /// Rebo code must only be executed once all `this*` have been found.
 /// There are currently 3 such `this`-pointers - rebo starts once the semaphore reaches 1.
@@ -86,18 +89,60 @@ pub struct Hooks {
     pub _ugameusersettings_applyresolutionsettings: &'static RawHook<RefunctIsaAbi, ()>,
     pub _uuserwidget_addtoscreen: &'static RawHook<RefunctIsaAbi, ()>,
     pub _amycharacter_tick: &'static RawHook<RefunctIsaAbi, ()>,
+    pub _amycharacter_underwaterchanged: &'static TypedHook<RefunctIsaAbi, fn(*mut AMyCharacterUE, bool), ()>,
+    /// only enabled while a rebo script is running, as it's called very often
+    pub uobject_processevent: &'static TypedHook<RefunctIsaAbi, fn(*mut UObject, *mut UFunction, *mut c_void), ()>,
 }
 
-pub fn init() -> Hooks {
+impl Hooks {
+    /// Restore the original code of all hooked functions.
+    pub fn disable(&self) {
+        self.fslateapplication.disable();
+        self._amycharacter_forceduncreouch.disable();
+        self._tick.disable();
+        self.aliftbase.disable();
+        self._amyhud_drawhud.disable();
+        self._ahud_drawmaterialsimple.disable();
+        self._ugameusersettings_applyresolutionsettings.disable();
+        self._uuserwidget_addtoscreen.disable();
+        self._amycharacter_tick.disable();
+        self._amycharacter_underwaterchanged.disable();
+        self.uobject_processevent.disable();
+    }
+
+    /// Disable all hooks and free their memory.
+    ///
+    /// # Safety
+    /// No thread may currently execute within any hook or call any hook afterwards.
+    pub unsafe fn remove(self) {
+        self.fslateapplication.remove();
+        self._amycharacter_forceduncreouch.remove();
+        self._tick.remove();
+        self.aliftbase.remove();
+        self._amyhud_drawhud.remove();
+        self._ahud_drawmaterialsimple.remove();
+        self._ugameusersettings_applyresolutionsettings.remove();
+        self._uuserwidget_addtoscreen.remove();
+        self._amycharacter_tick.remove();
+        self._amycharacter_underwaterchanged.remove();
+        self.uobject_processevent.remove();
+    }
+}
+
+/// Find and hook all functions we need, `None` if the game's layout isn't what we expect.
+pub fn init() -> Option<Hooks> {
     #[cfg(windows)] windows::init();
     #[cfg(unix)] linux::init();
+    if !character::init() {
+        return None;
+    }
     uworld::init();
     map_editor::init();
     font::init();
 
 
     unsafe {
-        Hooks {
+        Some(Hooks {
             fslateapplication: FSlateApplication::hook(),
             _amycharacter_forceduncreouch: RawHook::create(AMYCHARACTER_FORCEDUNCROUCH.load(Ordering::Relaxed), newgame::new_game_hook).enabled(),
             _tick: RawHook::create(UENGINE_UPDATETIMEANDHANDLEMAXTICKRATE.load(Ordering::Relaxed), tick::tick_hook).enabled(),
@@ -107,7 +152,9 @@ pub fn init() -> Hooks {
             _ugameusersettings_applyresolutionsettings: RawHook::create(UGAMEUSERSETTINGS_APPLYRESOLUTIONSETTINGS.load(Ordering::Relaxed), gameusersettings::apply_resolution_settings).enabled(),
             _uuserwidget_addtoscreen: RawHook::create(UUSERWIDGET_ADDTOSCREEN.load(Ordering::Relaxed), uworld::add_to_screen_hook).enabled(),
             _amycharacter_tick: RawHook::create(AMYCHARACTER_TICK.load(Ordering::Relaxed), character::tick_hook).enabled(),
-        }
+            _amycharacter_underwaterchanged: TypedHook::create(AMYCHARACTER_UNDERWATERCHANGED.load(Ordering::Relaxed), character::underwater_changed_hook).enabled(),
+            uobject_processevent: TypedHook::create(UOBJECT_PROCESSEVENT.load(Ordering::Relaxed), process_event::process_event_hook),
+        })
     }
 }
 
@@ -125,6 +172,14 @@ impl ALiftBase {
             }
         }
     }
+    fn disable(&self) {
+        self.addbasedcharacter.disable();
+        self.removebasedcharacter.disable();
+    }
+    unsafe fn remove(self) {
+        self.addbasedcharacter.remove();
+        self.removebasedcharacter.remove();
+    }
     pub unsafe fn add_based_character(&self, this: *mut ALiftBaseUE, character: *mut AMyCharacterUE) {
         self.addbasedcharacter.call_original_function((this, character));
     }
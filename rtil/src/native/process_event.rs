@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::sync::RwLock;
+use hook::{IsaAbi, TypedHook};
+use once_cell::sync::Lazy;
+use crate::native::{ClassWrapper, FunctionWrapper, ObjectWrapper, UFunction, UObject, UeObjectWrapper, UeScope};
+
+/// Report all `UObject::ProcessEvent` calls where any class of the object's class hierarchy
+/// matches `class_glob` and the function name matches `function_glob`.
+/// Globs support `*` (any number of characters) and `?` (exactly one character).
+#[derive(Debug, Clone)]
+pub struct ProcessEventFilter {
+    pub class_glob: String,
+    pub function_glob: String,
+}
+
+/// Classes whose begin- and end-overlap events are always reported, independent of the filters,
+/// to detect cubes being collected, pipes being travelled and springpads launching the player
+const OVERLAP_EVENT_CLASSES: &[&str] = &["BP_PowerCore_C", "BP_TravelPipe_C", "BP_Jumppad_C"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlap {
+    Begin,
+    End,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Matches {
+    filters: bool,
+    overlap: Option<Overlap>,
+}
+
+/// (internal index, serial number) of an object, which unlike its address isn't reused after GC
+type ObjectKey = (i32, i32);
+
+struct Filters {
+    filters: Vec<ProcessEventFilter>,
+    /// (class, function) -> matches, to not compare names on every call of the hot function
+    cache: HashMap<(ObjectKey, ObjectKey), Matches>,
+}
+
+// only written when filters change or a (class, function) pair is seen for the first time,
+// so concurrent `ProcessEvent` calls don't contend for the lock
+static FILTERS: Lazy<RwLock<Filters>> = Lazy::new(|| RwLock::new(Filters { filters: Vec::new(), cache: HashMap::new() }));
+
+pub fn add_process_event_filter(filter: ProcessEventFilter) {
+    let mut filters = FILTERS.write().unwrap();
+    filters.filters.push(filter);
+    filters.cache.clear();
+}
+pub fn clear_process_event_filters() {
+    let mut filters = FILTERS.write().unwrap();
+    filters.filters.clear();
+    filters.cache.clear();
+}
+
+pub fn process_event_hook<IA: IsaAbi>(
+    hook: &TypedHook<IA, fn(*mut UObject, *mut UFunction, *mut c_void), ()>,
+    this: *mut UObject, function: *mut UFunction, params: *mut c_void,
+) {
+    if !this.is_null() && !function.is_null() {
+        let matches = matches(this, function);
+        if let Some(overlap) = matches.overlap {
+            crate::threads::ue::actor_overlap(this, function, params, overlap);
+        }
+        if matches.filters {
+            crate::threads::ue::process_event(this, function, params);
+        }
+    }
+    unsafe { hook.call_original_function((this, function, params)); }
+}
+
+fn matches(this: *mut UObject, function: *mut UFunction) -> Matches {
+    UeScope::with(|scope| {
+        let object = unsafe { ObjectWrapper::new(this) };
+        let class = object.class();
+        let function = unsafe { FunctionWrapper::new(function) };
+        let key = (object_key(scope, &class), object_key(scope, &function));
+        if let Some(&matches) = FILTERS.read().unwrap().cache.get(&key) {
+            return matches;
+        }
+        let function_name = function.name();
+        let class_names: Vec<_> = std::iter::successors(Some(class), ClassWrapper::super_class)
+            .map(|class| class.name())
+            .collect();
+        let mut filters = FILTERS.write().unwrap();
+        let matches_filters = filters.filters.iter().any(|filter| {
+            glob_matches(&filter.function_glob, &function_name)
+                && class_names.iter().any(|class_name| glob_matches(&filter.class_glob, class_name))
+        });
+        let overlap_class = class_names.iter().any(|class_name| OVERLAP_EVENT_CLASSES.contains(&class_name.as_str()));
+        let overlap = if !overlap_class || !function_name.starts_with("BndEvt__") {
+            None
+        } else if function_name.contains("ComponentBeginOverlapSignature") {
+            Some(Overlap::Begin)
+        } else if function_name.contains("ComponentEndOverlapSignature") {
+            Some(Overlap::End)
+        } else {
+            None
+        };
+        let matches = Matches { filters: matches_filters, overlap };
+        filters.cache.insert(key, matches);
+        matches
+    })
+}
+
+fn object_key<'a, T: UeObjectWrapper<'a>>(scope: &'a UeScope, object: &T) -> ObjectKey {
+    let index = scope.object_index(object);
+    (index.internal_index(), index.serial_number())
+}
+
+fn glob_matches(glob: &str, s: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let s: Vec<char> = s.chars().collect();
+    let (mut g, mut i) = (0, 0);
+    // position of the last `*` in the glob and the position in `s` it currently matches up to
+    let mut star = None;
+    while i < s.len() {
+        match glob.get(g) {
+            Some('*') => {
+                star = Some((g, i));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == s[i] => {
+                g += 1;
+                i += 1;
+            }
+            _ => match star {
+                Some((star_g, star_i)) => {
+                    g = star_g + 1;
+                    i = star_i + 1;
+                    star = Some((star_g, star_i + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
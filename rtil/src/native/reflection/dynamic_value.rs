@@ -28,6 +28,22 @@ impl<'a> DynamicValue<'a> {
         }
     }
 
+    pub fn try_field(self, name: &str) -> Option<DynamicValue<'a>> {
+        if ObjectWrapper::can_be_created_from(&self.prop) {
+            self.unwrap_object()?.try_get_field(name)
+        } else if StructValueWrapper::can_be_created_from(&self.prop) {
+            self.unwrap::<StructValueWrapper<'a>>().try_get_field(name)
+        } else {
+            None
+        }
+    }
+
+    /// Get the object of an object property, `None` if it's null
+    pub fn unwrap_object(self) -> Option<ObjectWrapper<'a>> {
+        ObjectWrapper::check_property_type(&self.prop);
+        unsafe { ObjectWrapper::new_nullable(*(self.ptr as *mut *mut UObject)) }
+    }
+
     pub fn unwrap<T: ArrayElement<'a>>(self) -> T {
         unsafe { T::create(self.ptr, &self.prop) }
     }
@@ -53,4 +69,16 @@ impl<'a> DynamicValue<'a> {
         let ptr = self.ptr as *mut *mut UObject;
         unsafe { *ptr = object.as_ptr() }
     }
+
+    pub fn set_object_null(&self) {
+        ObjectWrapper::check_property_type(&self.prop);
+        let ptr = self.ptr as *mut *mut UObject;
+        unsafe { *ptr = std::ptr::null_mut() }
+    }
+}
+
+impl<'a> ArrayElement<'a> for DynamicValue<'a> {
+    unsafe fn create(ptr: *mut c_void, prop: &PropertyWrapper<'a>) -> DynamicValue<'a> {
+        DynamicValue::new(ptr, prop.clone())
+    }
 }
@@ -0,0 +1,73 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::native::reflection::{ClassWrapper, ObjectPropertyWrapper, StructWrapper};
+
+/// Offset of a field within a native UE struct.
+///
+/// Starts out as the offset of the field in our static `#[repr(C)]` definition and is
+/// replaced with the offset of the UE property of the same field during `init`.
+pub struct FieldOffset {
+    property_name: &'static str,
+    static_offset: usize,
+    offset: AtomicUsize,
+    resolved: AtomicBool,
+}
+
+impl FieldOffset {
+    pub const fn new(property_name: &'static str, static_offset: usize) -> FieldOffset {
+        FieldOffset {
+            property_name,
+            static_offset,
+            offset: AtomicUsize::new(static_offset),
+            resolved: AtomicBool::new(false),
+        }
+    }
+
+    pub fn get(&self) -> usize {
+        self.offset.load(Ordering::Relaxed)
+    }
+
+    /// Pointer to this field within the struct `base` points to.
+    pub unsafe fn field<S, T>(&self, base: *mut S) -> *mut T {
+        base.cast::<u8>().add(self.get()).cast()
+    }
+
+    pub fn property_name(&self) -> &'static str {
+        self.property_name
+    }
+
+    /// Whether the offset was found via reflection rather than taken from the static layout.
+    pub fn is_resolved(&self) -> bool {
+        self.resolved.load(Ordering::Relaxed)
+    }
+
+    pub fn matches_static_layout(&self) -> bool {
+        self.get() == self.static_offset
+    }
+
+    /// Resolve the offset from the reflection data of `struct_`.
+    ///
+    /// Logs if the offset differs from the static layout, in which case the reflected offset is used.
+    /// If the property can't be found, the static offset is kept and the field stays unresolved.
+    pub fn resolve(&self, struct_: &StructWrapper) {
+        let struct_name = struct_.name();
+        let Some(prop) = struct_.find_property(self.property_name) else {
+            log!("SELF-CHECK: {struct_name}::{} not found via reflection, using static offset {:#x}", self.property_name, self.static_offset);
+            return;
+        };
+        let offset: usize = prop.offset().try_into().unwrap();
+        self.offset.store(offset, Ordering::Relaxed);
+        self.resolved.store(true, Ordering::Relaxed);
+        if offset != self.static_offset {
+            log!("SELF-CHECK: {struct_name}::{} is at {offset:#x} according to reflection, but at {:#x} in the static layout", self.property_name, self.static_offset);
+        } else {
+            log!("SELF-CHECK: {struct_name}::{} at {offset:#x}", self.property_name);
+        }
+    }
+
+    /// Resolve the offset of this object property and return the class of the referenced object.
+    pub fn resolve_object<'a>(&self, struct_: &StructWrapper<'a>) -> Option<ClassWrapper<'a>> {
+        self.resolve(struct_);
+        let prop = struct_.find_property(self.property_name)?.try_upcast::<ObjectPropertyWrapper>()?;
+        Some(prop.property_class())
+    }
+}
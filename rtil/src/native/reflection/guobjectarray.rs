@@ -21,6 +21,22 @@ impl<T: UeObjectWrapperType> Clone for ObjectIndex<T> {
 }
 // get rid of the implied T: Clone bound of derive
 impl<T: UeObjectWrapperType> Copy for ObjectIndex<T> {}
+impl<T: UeObjectWrapperType> ObjectIndex<T> {
+    /// Reconstruct an index previously split up with `internal_index` and `serial_number`.
+    /// Resolving it checks that the object is still the same one.
+    pub fn from_raw(internal_index: i32, serial_number: i32) -> ObjectIndex<T> {
+        ObjectIndex {
+            index: UntypedObjectIndex { internal_index, serial_number },
+            _marker: PhantomData,
+        }
+    }
+    pub fn internal_index(&self) -> i32 {
+        self.index.internal_index
+    }
+    pub fn serial_number(&self) -> i32 {
+        self.index.serial_number
+    }
+}
 #[derive(Debug, Clone, Copy)]
 struct UntypedObjectIndex {
     internal_index: i32,
@@ -72,7 +88,7 @@ impl UeScope {
     fn resolve_untyped_object_index<'a>(&'a self, index: UntypedObjectIndex) -> Result<ObjectWrapper<'a>, GetUeObjectError> {
         let item = self.object_array().try_get(index.internal_index)
             .ok_or(GetUeObjectError::Invalidated)?;
-        if item.serial_number() != index.serial_number {
+        if item.serial_number() != index.serial_number || unsafe { (*item.as_ptr()).object.is_null() } {
             return Err(GetUeObjectError::Invalidated);
         }
         Ok(item.object())
@@ -81,6 +97,10 @@ impl UeScope {
         let object = self.resolve_untyped_object_index(index.borrow().index).unwrap();
         object.upcast()
     }
+    pub fn try_get<'a, T: UeObjectWrapperType>(&'a self, index: impl Borrow<ObjectIndex<T>>) -> Result<T::UeObjectWrapper<'a>, GetUeObjectError> {
+        let object = self.resolve_untyped_object_index(index.borrow().index)?;
+        object.try_upcast().ok_or(GetUeObjectError::Invalidated)
+    }
 }
 
 /// Wraps the global FUObjectArray (symbol GUObjectArray)
@@ -161,7 +181,7 @@ impl<'a> ObjectArrayWrapper<'a> {
         self.try_get(internal_index).unwrap_or_else(|| panic!("assert {} < {}", internal_index, self.num_elements()))
     }
     pub fn try_get(&self, internal_index: i32) -> Option<ObjectItemWrapper<'a>> {
-        let index: usize = internal_index.try_into().unwrap();
+        let index: usize = internal_index.try_into().ok()?;
         unsafe {
             if index >= self.num_elements() {
                 None
@@ -184,7 +204,7 @@ impl<'a> ObjectItemWrapper<'a> {
         assert!(!item.is_null());
         ObjectItemWrapper { item, _marker: PhantomData }
     }
-    pub fn _as_ptr(&self) -> *mut FUObjectItem {
+    pub fn as_ptr(&self) -> *mut FUObjectItem {
         self.item
     }
 
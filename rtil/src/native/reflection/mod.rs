@@ -2,7 +2,7 @@ use std::ffi::c_void;
 use std::fmt::Pointer;
 use std::cell::Cell;
 use std::ops::Deref;
-use crate::native::ue::{FName, TArray, UeU64};
+use crate::native::ue::{FName, FString, TArray, UeU64};
 
 mod dynamic_value;
 pub use dynamic_value::*;
@@ -10,6 +10,10 @@ mod wrappers;
 pub use wrappers::*;
 mod guobjectarray;
 pub use guobjectarray::*;
+mod field_offset;
+pub use field_offset::*;
+mod sdk;
+pub use sdk::*;
 
 pub trait UeObjectWrapperType {
     type UeObjectWrapper<'a>: UeObjectWrapper<'a, UeObjectWrapperType = Self>;
@@ -125,6 +129,22 @@ impl_array_element_for_primitives! {
     u64, "UInt64Property";
     f32, "FloatProperty";
     f64, "DoubleProperty";
+    FName, "NameProperty";
+}
+unsafe impl<'a> SizedArrayElement<'a> for &'a Cell<FString> {
+    type ElementType = FString;
+
+    fn check_property_type(prop: &PropertyWrapper<'a>) {
+        assert_eq!(prop.class().name(), "StrProperty");
+    }
+
+    fn can_be_created_from(prop: &PropertyWrapper<'a>) -> bool {
+        prop.class().name() == "StrProperty"
+    }
+
+    unsafe fn create(ptr: *mut Self::ElementType) -> &'a Cell<FString> {
+        &*(ptr as *const _ as *const _)
+    }
 }
 
 #[repr(C)]
@@ -138,7 +158,7 @@ pub struct UObject {
     pub internal_index: i32,
     pub class: *mut UClass,
     pub name: FName,
-    pub _outer_private: *const (),
+    pub outer_private: *mut UObject,
 }
 #[repr(C)]
 pub struct AActor {
@@ -206,7 +226,13 @@ pub struct UProperty {
     pub post_construct_link_next: *mut UProperty,
 }
 
-// Byte, Int*, UInt* are all empty
+// Int*, UInt* are all empty
+#[repr(C)]
+pub struct UByteProperty {
+    pub base_uproperty: UProperty,
+    // null if the byte isn't an enum
+    pub enum_: *mut UEnum,
+}
 #[repr(C)]
 pub struct UBoolProperty {
     base_uproperty: UProperty,
@@ -223,12 +249,12 @@ pub struct UObjectProperty {
     pub property_class: *mut UClass,
 }
 #[repr(C)]
-pub struct _UClassProperty {
-    pub base_uproperty: UProperty,
+pub struct UClassProperty {
+    pub base_uobjectproperty: UObjectProperty,
     pub meta_class: *mut UClass,
 }
 #[repr(C)]
-pub struct _UInterfaceProperty {
+pub struct UInterfaceProperty {
     pub base_uproperty: UProperty,
     pub interface_class: *mut UClass,
 }
@@ -238,16 +264,18 @@ pub struct UArrayProperty {
     pub inner: *mut UProperty,
 }
 #[repr(C)]
-pub struct _UMapProperty {
+pub struct UMapProperty {
     pub base_uproperty: UProperty,
     pub key_prop: *mut UProperty,
     pub value_prop: *mut UProperty,
+    // we calculate the layout ourselves in MapWrapper
     //pub map_layout: FScriptMapLayout,
 }
 #[repr(C)]
-pub struct _USetProperty {
+pub struct USetProperty {
     pub base_uproperty: UProperty,
     pub element_prop: *mut UProperty,
+    // we calculate the layout ourselves in SetWrapper
     //pub set_layout: FScriptSetLayout,
 }
 #[repr(C)]
@@ -257,21 +285,83 @@ pub struct UStructProperty {
     pub struct_: *mut UStruct,
 }
 #[repr(C)]
-pub struct _UDelegateProperty {
+pub struct UDelegateProperty {
     pub base_uproperty: UProperty,
-    //pub signature_function: *mut UFunction,
+    pub signature_function: *mut UFunction,
 }
 #[repr(C)]
-pub struct _UMulticastDelegateProperty {
+pub struct UMulticastDelegateProperty {
     pub base_uproperty: UProperty,
-    //pub signature_function: *mut UFunction,
+    pub signature_function: *mut UFunction,
 }
 // {UMulticastInlineDelegateProperty, UMulticastSparseDelegateProperty} : UMulticastDelegateProperty
 #[repr(C)]
-pub struct _UEnumProperty {
+pub struct UEnumProperty {
     pub base_uproperty: UProperty,
     // UNumericProperty - Byte, Int*, UInt*
     pub underlying_prop: *mut UProperty,
-    //pub enum_: *mut UEnum,
+    pub enum_: *mut UEnum,
+}
+
+#[repr(C)]
+pub struct UEnum {
+    pub base_ufield: UField,
+    pub cpp_type: FString,
+    pub names: TArray<FEnumName>,
+    // ECppForm
+    pub cpp_form: i32,
+}
+/// `TPair<FName, uint8>` (UE < 4.16 stores enum values as uint8)
+#[repr(C)]
+pub struct FEnumName {
+    pub name: FName,
+    pub value: u8,
 }
 
+/// TSparseArray
+#[repr(C)]
+pub struct FScriptSparseArray {
+    pub data: TArray<u8>,
+    pub allocation_flags: FScriptBitArray,
+    pub first_free_index: i32,
+    pub num_free_indices: i32,
+}
+/// TBitArray with its default TInlineAllocator<4>
+#[repr(C)]
+pub struct FScriptBitArray {
+    pub inline_data: [u32; 4],
+    pub secondary_data: *mut u32,
+    pub num_bits: i32,
+    pub max_bits: i32,
+}
+/// TSet, also used for TMap which is a TSet<TPair<K, V>>
+#[repr(C)]
+pub struct FScriptSet {
+    pub elements: FScriptSparseArray,
+    // TInlineAllocator<1>::ForElementType<FSetElementId>
+    pub hash_inline: i32,
+    pub hash_secondary: *mut i32,
+    pub hash_size: i32,
+}
+/// FScriptDelegate / TScriptDelegate<FWeakObjectPtr>
+#[repr(C)]
+pub struct FScriptDelegate {
+    pub object: FWeakObjectPtr,
+    pub function_name: FName,
+}
+#[repr(C)]
+pub struct FWeakObjectPtr {
+    pub object_index: i32,
+    pub object_serial_number: i32,
+}
+/// FMulticastScriptDelegate / TMulticastScriptDelegate<FWeakObjectPtr>
+#[repr(C)]
+pub struct FMulticastScriptDelegate {
+    pub invocation_list: TArray<FScriptDelegate>,
+}
+/// FScriptInterface
+#[repr(C)]
+pub struct FScriptInterface {
+    pub object: *mut UObject,
+    pub interface: *mut c_void,
+}
@@ -0,0 +1,77 @@
+use sdk_dump::{FunctionDump, PropertyDump, SdkDump, StructDump};
+use crate::native::reflection::{FunctionWrapper, ObjectWrapper, PropertyWrapper, StructWrapper, UeScope};
+
+/// Walk the GUObjectArray and dump all classes, structs and functions.
+pub fn dump_sdk(scope: &UeScope) -> SdkDump {
+    let mut dump = SdkDump::default();
+    for item in scope.iter_global_object_array() {
+        let object = item.object();
+        let class = object.class();
+        if let Some(function) = object.try_upcast::<FunctionWrapper>() {
+            dump.functions.push(dump_function(&function));
+        } else if class.extends_from("Class") {
+            dump.classes.push(dump_struct(&object.upcast()));
+        } else if class.extends_from("ScriptStruct") {
+            dump.structs.push(dump_struct(&object.upcast()));
+        }
+    }
+    dump
+}
+
+fn dump_struct(struct_: &StructWrapper) -> StructDump {
+    let mut super_chain = Vec::new();
+    let mut super_struct = struct_.super_struct();
+    while let Some(s) = super_struct {
+        super_chain.push(s.name());
+        super_struct = s.super_struct();
+    }
+    StructDump {
+        outer: struct_.outer().map(|outer| path_name(&outer)).unwrap_or_default(),
+        name: struct_.name(),
+        super_chain,
+        size: struct_.properties_size(),
+        min_alignment: struct_.min_alignment(),
+        properties: own_properties(struct_).map(|prop| dump_property(&prop)).collect(),
+    }
+}
+
+fn dump_function(function: &FunctionWrapper) -> FunctionDump {
+    FunctionDump {
+        owner: function.outer().map(|outer| path_name(&outer)).unwrap_or_default(),
+        name: function.name(),
+        flags: function.function_flags(),
+        parms_size: function.parms_size(),
+        return_value_offset: function.return_value_offset(),
+        params: function.iter_params().map(|prop| dump_property(&prop)).collect(),
+    }
+}
+
+fn dump_property(prop: &PropertyWrapper) -> PropertyDump {
+    PropertyDump {
+        name: prop.name(),
+        kind: prop.property_kind(),
+        type_name: prop.type_name(),
+        offset: prop.offset(),
+        size: prop.size(),
+        array_dim: prop.array_dim(),
+        flags: prop.property_flags(),
+    }
+}
+
+/// outers and name of the object joined with `.`, e.g. `/Script/Engine.Actor`
+fn path_name(object: &ObjectWrapper) -> String {
+    let mut path = object.name();
+    let mut outer = object.outer();
+    while let Some(o) = outer {
+        path = format!("{}.{path}", o.name());
+        outer = o.outer();
+    }
+    path
+}
+
+/// properties declared by the struct itself, excluding inherited ones
+fn own_properties<'a>(struct_: &StructWrapper<'a>) -> impl Iterator<Item = PropertyWrapper<'a>> {
+    struct_.children().into_iter()
+        .flat_map(|children| children.iter_this_and_next_fields())
+        .filter_map(|field| field.try_upcast::<PropertyWrapper>())
+}
@@ -3,11 +3,12 @@ use std::cell::Cell;
 use std::ffi::c_void;
 use std::fmt::{Display, Formatter, Pointer};
 use std::marker::PhantomData;
+use std::{mem, ptr};
 use std::ops::Deref;
 use std::sync::atomic::Ordering;
 use itertools::Itertools;
-use crate::native::reflection::{AActor, DynamicValue, UArrayProperty, UClass, UeObjectWrapper, UObject, UObjectProperty, UProperty, UStruct, UStructProperty};
-use crate::native::ue::TArray;
+use crate::native::reflection::{AActor, DynamicValue, FMulticastScriptDelegate, FScriptDelegate, FScriptInterface, FScriptSet, FScriptSparseArray, GlobalObjectArrayWrapper, UArrayProperty, UByteProperty, UClass, UClassProperty, UDelegateProperty, UEnum, UEnumProperty, UeObjectWrapper, UInterfaceProperty, UMapProperty, UMulticastDelegateProperty, UObject, UObjectProperty, UProperty, USetProperty, UStruct, UStructProperty};
+use crate::native::ue::{TArray, UeU64};
 use crate::native::{ArrayElement, UBoolProperty, UeObjectWrapperType, UField, UFunction, UOBJECT_PROCESSEVENT};
 
 #[derive(Debug, Clone)]
@@ -32,7 +33,7 @@ impl<'a> BoolValueWrapper<'a> {
         assert!(!ptr.is_null());
         BoolValueWrapper { ptr, bool_property, _marker: PhantomData }
     }
-    pub fn _get(&self) -> bool {
+    pub fn get(&self) -> bool {
         unsafe {
             let ptr = self.ptr.offset(self.bool_property.byte_offset() as isize);
             if self.bool_property.field_mask() == 0xff {
@@ -96,6 +97,12 @@ impl<'a> StructValueWrapper<'a> {
             apply_field_info(self.ptr, field_info)
         }
     }
+    pub fn try_get_field(&self, name: &str) -> Option<DynamicValue<'a>> {
+        unsafe {
+            let field_info = self.struct_information.try_get_field_info(name, self.limit_num_fields)?;
+            Some(apply_field_info(self.ptr, field_info))
+        }
+    }
     pub fn can_be_created_from(prop: &PropertyWrapper<'a>) -> bool {
         prop.try_upcast::<StructPropertyWrapper<'a>>().is_some()
     }
@@ -195,6 +202,9 @@ impl<'a> ObjectWrapper<'a> {
     pub fn class(&self) -> ClassWrapper<'a> {
         unsafe { ClassWrapper::new((*self.object).class) }
     }
+    pub fn outer(&self) -> Option<ObjectWrapper<'a>> {
+        unsafe { ObjectWrapper::new_nullable((*self.object).outer_private) }
+    }
 
     pub fn get_field(&self, name: &str) -> DynamicValue<'a> {
         unsafe {
@@ -202,6 +212,12 @@ impl<'a> ObjectWrapper<'a> {
             apply_field_info(self.object as *mut u8, field_info)
         }
     }
+    pub fn try_get_field(&self, name: &str) -> Option<DynamicValue<'a>> {
+        unsafe {
+            let field_info = self.class().try_get_field_info(name, usize::MAX)?;
+            Some(apply_field_info(self.object as *mut u8, field_info))
+        }
+    }
 
     pub fn upcast<T: UeObjectWrapper<'a>>(&self) -> T {
         self.try_upcast().unwrap_or_else(|| panic!("can't upcast {} to {}", self.class().name(), T::CLASS_NAME))
@@ -327,10 +343,54 @@ impl<'a> PropertyWrapper<'a> {
     pub fn size(&self) -> usize {
         unsafe { (*self.as_ptr()).element_size.try_into().unwrap() }
     }
+    pub fn array_dim(&self) -> i32 {
+        unsafe { (*self.as_ptr()).array_dim }
+    }
+    pub fn property_flags(&self) -> u64 {
+        unsafe { (*self.as_ptr()).property_flags.get() }
+    }
+    /// Approximation of `UProperty::GetMinAlignment` without going through the vtable
+    pub fn min_alignment(&self) -> usize {
+        if let Some(struct_prop) = self.try_upcast::<StructPropertyWrapper<'a>>() {
+            return struct_prop.struct_().min_alignment();
+        }
+        if self.try_upcast::<BoolPropertyWrapper<'a>>().is_some() {
+            return 1;
+        }
+        primitive_alignment(&self.property_kind(), self.size())
+    }
 
     pub fn iter_this_and_next_properties(&self) -> impl Iterator<Item = PropertyWrapper<'a>> {
         self.iter_this_and_next_fields().map(|field| field.upcast())
     }
+    /// C++-like type of the property, e.g. `TArray<BP_IslandChunk_C*>`
+    pub fn type_name(&self) -> String {
+        if let Some(class_prop) = self.try_upcast::<ClassPropertyWrapper<'a>>() {
+            format!("TSubclassOf<{}>", class_prop.meta_class().name())
+        } else if let Some(object_prop) = self.try_upcast::<ObjectPropertyWrapper<'a>>() {
+            format!("{}*", object_prop.property_class().name())
+        } else if let Some(interface_prop) = self.try_upcast::<InterfacePropertyWrapper<'a>>() {
+            format!("TScriptInterface<{}>", interface_prop.interface_class().name())
+        } else if let Some(struct_prop) = self.try_upcast::<StructPropertyWrapper<'a>>() {
+            struct_prop.struct_().name()
+        } else if let Some(array_prop) = self.try_upcast::<ArrayPropertyWrapper<'a>>() {
+            format!("TArray<{}>", array_prop.inner().type_name())
+        } else if let Some(set_prop) = self.try_upcast::<SetPropertyWrapper<'a>>() {
+            format!("TSet<{}>", set_prop.element_prop().type_name())
+        } else if let Some(map_prop) = self.try_upcast::<MapPropertyWrapper<'a>>() {
+            format!("TMap<{}, {}>", map_prop.key_prop().type_name(), map_prop.value_prop().type_name())
+        } else if let Some(enum_prop) = self.try_upcast::<EnumPropertyWrapper<'a>>() {
+            enum_prop.enum_().name()
+        } else if let Some(enum_) = self.try_upcast::<BytePropertyWrapper<'a>>().and_then(|byte| byte.enum_()) {
+            enum_.name()
+        } else if let Some(delegate_prop) = self.try_upcast::<DelegatePropertyWrapper<'a>>() {
+            format!("TDelegate<{}>", delegate_prop.signature_function().name())
+        } else if let Some(delegate_prop) = self.try_upcast::<MulticastDelegatePropertyWrapper<'a>>() {
+            format!("TMulticastDelegate<{}>", delegate_prop.signature_function().name())
+        } else {
+            self.property_kind()
+        }
+    }
 }
 impl<'a> Display for PropertyWrapper<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
@@ -512,6 +572,405 @@ impl<'a> BoolPropertyWrapper<'a> {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct BytePropertyWrapper<'a> {
+    base: PropertyWrapper<'a>,
+}
+pub enum BytePropertyWrapperType {}
+impl UeObjectWrapperType for BytePropertyWrapperType {
+    type UeObjectWrapper<'a> = BytePropertyWrapper<'a>;
+}
+unsafe impl<'a> UeObjectWrapper<'a> for BytePropertyWrapper<'a> {
+    type UeObjectWrapperType = BytePropertyWrapperType;
+    type Wrapping = UByteProperty;
+    const CLASS_NAME: &'static str = "ByteProperty";
+
+    unsafe fn create(ptr: *mut Self::Wrapping) -> BytePropertyWrapper<'a> {
+        BytePropertyWrapper::new(ptr)
+    }
+}
+impl<'a> Deref for BytePropertyWrapper<'a> {
+    type Target = PropertyWrapper<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+impl<'a> Pointer for BytePropertyWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.as_ptr(), f)
+    }
+}
+impl<'a> BytePropertyWrapper<'a> {
+    pub unsafe fn new(prop: *mut UByteProperty) -> BytePropertyWrapper<'a> {
+        assert!(!prop.is_null());
+        BytePropertyWrapper { base: PropertyWrapper::new(prop as *mut UProperty) }
+    }
+    pub fn as_ptr(&self) -> *mut UByteProperty {
+        self.base.as_ptr() as *mut UByteProperty
+    }
+    pub fn enum_(&self) -> Option<EnumWrapper<'a>> {
+        let enum_ = unsafe { (*self.as_ptr()).enum_ };
+        (!enum_.is_null()).then(|| unsafe { EnumWrapper::new(enum_) })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumPropertyWrapper<'a> {
+    base: PropertyWrapper<'a>,
+}
+pub enum EnumPropertyWrapperType {}
+impl UeObjectWrapperType for EnumPropertyWrapperType {
+    type UeObjectWrapper<'a> = EnumPropertyWrapper<'a>;
+}
+unsafe impl<'a> UeObjectWrapper<'a> for EnumPropertyWrapper<'a> {
+    type UeObjectWrapperType = EnumPropertyWrapperType;
+    type Wrapping = UEnumProperty;
+    const CLASS_NAME: &'static str = "EnumProperty";
+
+    unsafe fn create(ptr: *mut Self::Wrapping) -> EnumPropertyWrapper<'a> {
+        EnumPropertyWrapper::new(ptr)
+    }
+}
+impl<'a> Deref for EnumPropertyWrapper<'a> {
+    type Target = PropertyWrapper<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+impl<'a> Pointer for EnumPropertyWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.as_ptr(), f)
+    }
+}
+impl<'a> EnumPropertyWrapper<'a> {
+    pub unsafe fn new(prop: *mut UEnumProperty) -> EnumPropertyWrapper<'a> {
+        assert!(!prop.is_null());
+        EnumPropertyWrapper { base: PropertyWrapper::new(prop as *mut UProperty) }
+    }
+    pub fn as_ptr(&self) -> *mut UEnumProperty {
+        self.base.as_ptr() as *mut UEnumProperty
+    }
+    pub fn underlying_prop(&self) -> PropertyWrapper<'a> {
+        unsafe { PropertyWrapper::new((*self.as_ptr()).underlying_prop) }
+    }
+    pub fn enum_(&self) -> EnumWrapper<'a> {
+        unsafe { EnumWrapper::new((*self.as_ptr()).enum_) }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClassPropertyWrapper<'a> {
+    base: ObjectPropertyWrapper<'a>,
+}
+pub enum ClassPropertyWrapperType {}
+impl UeObjectWrapperType for ClassPropertyWrapperType {
+    type UeObjectWrapper<'a> = ClassPropertyWrapper<'a>;
+}
+unsafe impl<'a> UeObjectWrapper<'a> for ClassPropertyWrapper<'a> {
+    type UeObjectWrapperType = ClassPropertyWrapperType;
+    type Wrapping = UClassProperty;
+    const CLASS_NAME: &'static str = "ClassProperty";
+
+    unsafe fn create(ptr: *mut Self::Wrapping) -> ClassPropertyWrapper<'a> {
+        ClassPropertyWrapper::new(ptr)
+    }
+}
+impl<'a> Deref for ClassPropertyWrapper<'a> {
+    type Target = ObjectPropertyWrapper<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+impl<'a> Pointer for ClassPropertyWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.as_ptr(), f)
+    }
+}
+impl<'a> ClassPropertyWrapper<'a> {
+    pub unsafe fn new(prop: *mut UClassProperty) -> ClassPropertyWrapper<'a> {
+        assert!(!prop.is_null());
+        ClassPropertyWrapper { base: ObjectPropertyWrapper::new(prop as *mut UObjectProperty) }
+    }
+    pub fn as_ptr(&self) -> *mut UClassProperty {
+        self.base.as_ptr() as *mut UClassProperty
+    }
+    /// the class the referenced class must extend from (`TSubclassOf<MetaClass>`)
+    pub fn meta_class(&self) -> ClassWrapper<'a> {
+        unsafe { ClassWrapper::new((*self.as_ptr()).meta_class) }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct InterfacePropertyWrapper<'a> {
+    base: PropertyWrapper<'a>,
+}
+pub enum InterfacePropertyWrapperType {}
+impl UeObjectWrapperType for InterfacePropertyWrapperType {
+    type UeObjectWrapper<'a> = InterfacePropertyWrapper<'a>;
+}
+unsafe impl<'a> UeObjectWrapper<'a> for InterfacePropertyWrapper<'a> {
+    type UeObjectWrapperType = InterfacePropertyWrapperType;
+    type Wrapping = UInterfaceProperty;
+    const CLASS_NAME: &'static str = "InterfaceProperty";
+
+    unsafe fn create(ptr: *mut Self::Wrapping) -> InterfacePropertyWrapper<'a> {
+        InterfacePropertyWrapper::new(ptr)
+    }
+}
+impl<'a> Deref for InterfacePropertyWrapper<'a> {
+    type Target = PropertyWrapper<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+impl<'a> Pointer for InterfacePropertyWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.as_ptr(), f)
+    }
+}
+impl<'a> InterfacePropertyWrapper<'a> {
+    pub unsafe fn new(prop: *mut UInterfaceProperty) -> InterfacePropertyWrapper<'a> {
+        assert!(!prop.is_null());
+        InterfacePropertyWrapper { base: PropertyWrapper::new(prop as *mut UProperty) }
+    }
+    pub fn as_ptr(&self) -> *mut UInterfaceProperty {
+        self.base.as_ptr() as *mut UInterfaceProperty
+    }
+    pub fn interface_class(&self) -> ClassWrapper<'a> {
+        unsafe { ClassWrapper::new((*self.as_ptr()).interface_class) }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MapPropertyWrapper<'a> {
+    base: PropertyWrapper<'a>,
+}
+pub enum MapPropertyWrapperType {}
+impl UeObjectWrapperType for MapPropertyWrapperType {
+    type UeObjectWrapper<'a> = MapPropertyWrapper<'a>;
+}
+unsafe impl<'a> UeObjectWrapper<'a> for MapPropertyWrapper<'a> {
+    type UeObjectWrapperType = MapPropertyWrapperType;
+    type Wrapping = UMapProperty;
+    const CLASS_NAME: &'static str = "MapProperty";
+
+    unsafe fn create(ptr: *mut Self::Wrapping) -> MapPropertyWrapper<'a> {
+        MapPropertyWrapper::new(ptr)
+    }
+}
+impl<'a> Deref for MapPropertyWrapper<'a> {
+    type Target = PropertyWrapper<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+impl<'a> Pointer for MapPropertyWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.as_ptr(), f)
+    }
+}
+impl<'a> MapPropertyWrapper<'a> {
+    pub unsafe fn new(prop: *mut UMapProperty) -> MapPropertyWrapper<'a> {
+        assert!(!prop.is_null());
+        MapPropertyWrapper { base: PropertyWrapper::new(prop as *mut UProperty) }
+    }
+    pub fn as_ptr(&self) -> *mut UMapProperty {
+        self.base.as_ptr() as *mut UMapProperty
+    }
+    pub fn key_prop(&self) -> PropertyWrapper<'a> {
+        unsafe { PropertyWrapper::new((*self.as_ptr()).key_prop) }
+    }
+    pub fn value_prop(&self) -> PropertyWrapper<'a> {
+        unsafe { PropertyWrapper::new((*self.as_ptr()).value_prop) }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SetPropertyWrapper<'a> {
+    base: PropertyWrapper<'a>,
+}
+pub enum SetPropertyWrapperType {}
+impl UeObjectWrapperType for SetPropertyWrapperType {
+    type UeObjectWrapper<'a> = SetPropertyWrapper<'a>;
+}
+unsafe impl<'a> UeObjectWrapper<'a> for SetPropertyWrapper<'a> {
+    type UeObjectWrapperType = SetPropertyWrapperType;
+    type Wrapping = USetProperty;
+    const CLASS_NAME: &'static str = "SetProperty";
+
+    unsafe fn create(ptr: *mut Self::Wrapping) -> SetPropertyWrapper<'a> {
+        SetPropertyWrapper::new(ptr)
+    }
+}
+impl<'a> Deref for SetPropertyWrapper<'a> {
+    type Target = PropertyWrapper<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+impl<'a> Pointer for SetPropertyWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.as_ptr(), f)
+    }
+}
+impl<'a> SetPropertyWrapper<'a> {
+    pub unsafe fn new(prop: *mut USetProperty) -> SetPropertyWrapper<'a> {
+        assert!(!prop.is_null());
+        SetPropertyWrapper { base: PropertyWrapper::new(prop as *mut UProperty) }
+    }
+    pub fn as_ptr(&self) -> *mut USetProperty {
+        self.base.as_ptr() as *mut USetProperty
+    }
+    pub fn element_prop(&self) -> PropertyWrapper<'a> {
+        unsafe { PropertyWrapper::new((*self.as_ptr()).element_prop) }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DelegatePropertyWrapper<'a> {
+    base: PropertyWrapper<'a>,
+}
+pub enum DelegatePropertyWrapperType {}
+impl UeObjectWrapperType for DelegatePropertyWrapperType {
+    type UeObjectWrapper<'a> = DelegatePropertyWrapper<'a>;
+}
+unsafe impl<'a> UeObjectWrapper<'a> for DelegatePropertyWrapper<'a> {
+    type UeObjectWrapperType = DelegatePropertyWrapperType;
+    type Wrapping = UDelegateProperty;
+    const CLASS_NAME: &'static str = "DelegateProperty";
+
+    unsafe fn create(ptr: *mut Self::Wrapping) -> DelegatePropertyWrapper<'a> {
+        DelegatePropertyWrapper::new(ptr)
+    }
+}
+impl<'a> Deref for DelegatePropertyWrapper<'a> {
+    type Target = PropertyWrapper<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+impl<'a> Pointer for DelegatePropertyWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.as_ptr(), f)
+    }
+}
+impl<'a> DelegatePropertyWrapper<'a> {
+    pub unsafe fn new(prop: *mut UDelegateProperty) -> DelegatePropertyWrapper<'a> {
+        assert!(!prop.is_null());
+        DelegatePropertyWrapper { base: PropertyWrapper::new(prop as *mut UProperty) }
+    }
+    pub fn as_ptr(&self) -> *mut UDelegateProperty {
+        self.base.as_ptr() as *mut UDelegateProperty
+    }
+    pub fn signature_function(&self) -> FunctionWrapper<'a> {
+        unsafe { FunctionWrapper::new((*self.as_ptr()).signature_function) }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MulticastDelegatePropertyWrapper<'a> {
+    base: PropertyWrapper<'a>,
+}
+pub enum MulticastDelegatePropertyWrapperType {}
+impl UeObjectWrapperType for MulticastDelegatePropertyWrapperType {
+    type UeObjectWrapper<'a> = MulticastDelegatePropertyWrapper<'a>;
+}
+unsafe impl<'a> UeObjectWrapper<'a> for MulticastDelegatePropertyWrapper<'a> {
+    type UeObjectWrapperType = MulticastDelegatePropertyWrapperType;
+    type Wrapping = UMulticastDelegateProperty;
+    const CLASS_NAME: &'static str = "MulticastDelegateProperty";
+
+    unsafe fn create(ptr: *mut Self::Wrapping) -> MulticastDelegatePropertyWrapper<'a> {
+        MulticastDelegatePropertyWrapper::new(ptr)
+    }
+}
+impl<'a> Deref for MulticastDelegatePropertyWrapper<'a> {
+    type Target = PropertyWrapper<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+impl<'a> Pointer for MulticastDelegatePropertyWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.as_ptr(), f)
+    }
+}
+impl<'a> MulticastDelegatePropertyWrapper<'a> {
+    pub unsafe fn new(prop: *mut UMulticastDelegateProperty) -> MulticastDelegatePropertyWrapper<'a> {
+        assert!(!prop.is_null());
+        MulticastDelegatePropertyWrapper { base: PropertyWrapper::new(prop as *mut UProperty) }
+    }
+    pub fn as_ptr(&self) -> *mut UMulticastDelegateProperty {
+        self.base.as_ptr() as *mut UMulticastDelegateProperty
+    }
+    pub fn signature_function(&self) -> FunctionWrapper<'a> {
+        unsafe { FunctionWrapper::new((*self.as_ptr()).signature_function) }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumWrapper<'a> {
+    base: FieldWrapper<'a>,
+}
+pub enum EnumWrapperType {}
+impl UeObjectWrapperType for EnumWrapperType {
+    type UeObjectWrapper<'a> = EnumWrapper<'a>;
+}
+unsafe impl<'a> UeObjectWrapper<'a> for EnumWrapper<'a> {
+    type UeObjectWrapperType = EnumWrapperType;
+    type Wrapping = UEnum;
+    const CLASS_NAME: &'static str = "Enum";
+
+    unsafe fn create(ptr: *mut Self::Wrapping) -> EnumWrapper<'a> {
+        EnumWrapper::new(ptr)
+    }
+}
+impl<'a> Deref for EnumWrapper<'a> {
+    type Target = FieldWrapper<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.base
+    }
+}
+impl<'a> Pointer for EnumWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.as_ptr(), f)
+    }
+}
+impl<'a> EnumWrapper<'a> {
+    pub unsafe fn new(enum_: *mut UEnum) -> EnumWrapper<'a> {
+        assert!(!enum_.is_null());
+        EnumWrapper { base: FieldWrapper::new(enum_ as *mut UField) }
+    }
+    pub fn as_ptr(&self) -> *mut UEnum {
+        self.base.as_ptr() as *mut UEnum
+    }
+    pub fn cpp_type(&self) -> String {
+        unsafe { (*self.as_ptr()).cpp_type.to_string_lossy() }
+    }
+    /// (name, value) of all enumerators, names are qualified (`Enum::Variant`) for namespaced and enum-class enums
+    pub fn names(&self) -> Vec<(String, i64)> {
+        unsafe { (*self.as_ptr()).names.into_iter().map(|e| (e.name.to_string_lossy(), e.value as i64)).collect() }
+    }
+    pub fn name_of_value(&self, value: i64) -> Option<String> {
+        unsafe { (*self.as_ptr()).names.into_iter().find(|e| e.value as i64 == value).map(|e| e.name.to_string_lossy()) }
+    }
+    /// accepts both qualified (`Enum::Variant`) and unqualified (`Variant`) names
+    pub fn value_of_name(&self, name: &str) -> Option<i64> {
+        self.names().into_iter()
+            .find(|(n, _)| n == name || n.rsplit_once("::").is_some_and(|(_, unqualified)| unqualified == name))
+            .map(|(_, value)| value)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StructWrapper<'a> {
     base: FieldWrapper<'a>,
@@ -618,7 +1077,13 @@ impl<'a> StructWrapper<'a> {
         self.iter_functions().find(|f| f.name() == name)
     }
 
-    fn get_field_info(&self, mut name: &str, limit_num_fields: usize) -> FieldInfo<'a> {
+    fn get_field_info(&self, name: &str, limit_num_fields: usize) -> FieldInfo<'a> {
+        self.try_get_field_info(name, limit_num_fields)
+            .unwrap_or_else(|| panic!("cannot access property {name} of type {}, properties available: {}", self.class().name(),
+                self.iter_properties().take(limit_num_fields).map(|prop| format!("{} {}", prop.class().name(), prop.name())).join(", "),
+            ))
+    }
+    fn try_get_field_info(&self, mut name: &str, limit_num_fields: usize) -> Option<FieldInfo<'a>> {
         let hacked_absolute = (name == "AbsoluteLocation" || name == "AbsoluteRotation" || name == "AbsoluteScale3D") && self.extends_from("SceneComponent");
         if hacked_absolute {
             match name {
@@ -629,10 +1094,7 @@ impl<'a> StructWrapper<'a> {
             }
         }
         let prop = self.iter_properties().take(limit_num_fields)
-            .find(|prop| prop.name() == name)
-            .unwrap_or_else(|| panic!("cannot access property {name} of type {}, properties available: {}", self.class().name(),
-                self.iter_properties().take(limit_num_fields).map(|prop| format!("{} {}", prop.class().name(), prop.name())).join(", "),
-            )).upcast::<PropertyWrapper>();
+            .find(|prop| prop.name() == name)?;
         let offset = if hacked_absolute {
             match name {
                 "RelativeLocation" => {
@@ -652,7 +1114,7 @@ impl<'a> StructWrapper<'a> {
         } else {
             prop.offset()
         };
-        FieldInfo { offset, prop }
+        Some(FieldInfo { offset, prop })
     }
 
     pub fn extends_from(&self, name: &str) -> bool {
@@ -754,6 +1216,12 @@ impl<'a> FunctionWrapper<'a> {
     pub fn parms_size(&self) -> u16 {
         unsafe { (*self.as_ptr()).parms_size }
     }
+    pub fn function_flags(&self) -> u32 {
+        unsafe { (*self.as_ptr()).function_flags }
+    }
+    pub fn return_value_offset(&self) -> u16 {
+        unsafe { (*self.as_ptr()).return_value_offset }
+    }
 
     pub fn iter_params(&self) -> impl Iterator<Item = PropertyWrapper<'a>> {
         self.iter_fields().take(self.num_parms() as usize).map(|field| field.upcast())
@@ -925,6 +1393,29 @@ impl<'a> ActorWrapper<'a> {
             extent.get_field("X").unwrap(), extent.get_field("Y").unwrap(), extent.get_field("Z").unwrap(),
         )
     }
+    pub fn is_hidden_in_game(&self) -> bool {
+        self.get_field("bHidden").unwrap::<BoolValueWrapper>().get()
+    }
+    /// Hide the actor and disable its collision, such that it can't be interacted with anymore
+    pub fn set_hidden_in_game(&self, hidden: bool) {
+        let fun = self.class().find_function("SetActorHiddenInGame").unwrap();
+        let params = fun.create_argument_struct();
+        params.get_field("bNewHidden").unwrap::<BoolValueWrapper>().set(hidden);
+        unsafe { fun.call(self.as_ptr(), &params) };
+        AActor::set_actor_enable_collision(self.as_ptr(), !hidden);
+    }
+    /// Attach the actor's root component to the component `other`'s root component is attached to,
+    /// keeping its world transform.
+    pub fn attach_like(&self, other: &ActorWrapper) {
+        let other_root: ObjectWrapper = other.get_field("RootComponent").unwrap();
+        let Some(parent) = other_root.get_field("AttachParent").unwrap_object() else { return };
+        let root: ObjectWrapper = self.get_field("RootComponent").unwrap();
+        let fun = root.class().find_function("AttachTo").unwrap();
+        let params = fun.create_argument_struct();
+        params.get_field("InParent").set_object(&parent);
+        params.get_field("AttachType").unwrap::<&Cell<u8>>().set(1); // EAttachLocation::KeepWorldPosition
+        unsafe { fun.call(root.as_ptr(), &params) };
+    }
 }
 
 
@@ -966,6 +1457,9 @@ impl<'a, T: ArrayElement<'a>> ArrayWrapper<'a, T> {
     pub fn capacity(&self) -> usize {
         unsafe { (*self.array).capacity() }
     }
+    pub fn as_ptr(&self) -> *mut TArray<c_void> {
+        self.array as *mut TArray<c_void>
+    }
     pub fn get(&self, index: usize) -> Option<T> {
         unsafe {
             let index = (*self.array).check_index_for_indexing(index).ok()?;
@@ -1003,9 +1497,398 @@ impl<'a, 'b, T: ArrayElement<'a>> IntoIterator for &'b ArrayWrapper<'a, T> {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct EnumValueWrapper<'a> {
+    ptr: *mut c_void,
+    underlying_prop: PropertyWrapper<'a>,
+    enum_: Option<EnumWrapper<'a>>,
+}
+impl<'a> Pointer for EnumValueWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.ptr, f)
+    }
+}
+impl<'a> ArrayElement<'a> for EnumValueWrapper<'a> {
+    unsafe fn create(ptr: *mut c_void, prop: &PropertyWrapper<'a>) -> EnumValueWrapper<'a> {
+        let (underlying_prop, enum_) = match prop.try_upcast::<EnumPropertyWrapper<'a>>() {
+            Some(enum_prop) => (enum_prop.underlying_prop(), Some(enum_prop.enum_())),
+            None => (prop.clone(), prop.upcast::<BytePropertyWrapper<'a>>().enum_()),
+        };
+        EnumValueWrapper::new(ptr, underlying_prop, enum_)
+    }
+}
+impl<'a> EnumValueWrapper<'a> {
+    pub unsafe fn new(ptr: *mut c_void, underlying_prop: PropertyWrapper<'a>, enum_: Option<EnumWrapper<'a>>) -> EnumValueWrapper<'a> {
+        assert!(!ptr.is_null());
+        EnumValueWrapper { ptr, underlying_prop, enum_ }
+    }
+    pub fn can_be_created_from(prop: &PropertyWrapper<'a>) -> bool {
+        prop.try_upcast::<EnumPropertyWrapper<'a>>().is_some()
+            || prop.try_upcast::<BytePropertyWrapper<'a>>().is_some_and(|byte| byte.enum_().is_some())
+    }
+    /// `None` for a plain ByteProperty without an enum
+    pub fn enum_(&self) -> Option<EnumWrapper<'a>> {
+        self.enum_.clone()
+    }
+    pub fn get(&self) -> Result<i64, UnknownEnumKind> {
+        let value = unsafe {
+            match self.underlying_prop.property_kind().as_str() {
+                "ByteProperty" => *(self.ptr as *const u8) as i64,
+                "Int8Property" => *(self.ptr as *const i8) as i64,
+                "UInt16Property" => *(self.ptr as *const u16) as i64,
+                "Int16Property" => *(self.ptr as *const i16) as i64,
+                "UInt32Property" => *(self.ptr as *const u32) as i64,
+                "IntProperty" => *(self.ptr as *const i32) as i64,
+                "UInt64Property" | "Int64Property" => (self.ptr as *const i64).read_unaligned(),
+                kind => return Err(UnknownEnumKind(kind.to_string())),
+            }
+        };
+        Ok(value)
+    }
+    pub fn set(&self, value: i64) -> Result<(), UnknownEnumKind> {
+        unsafe {
+            match self.underlying_prop.property_kind().as_str() {
+                "ByteProperty" | "Int8Property" => *(self.ptr as *mut u8) = value as u8,
+                "UInt16Property" | "Int16Property" => *(self.ptr as *mut u16) = value as u16,
+                "UInt32Property" | "IntProperty" => *(self.ptr as *mut u32) = value as u32,
+                "UInt64Property" | "Int64Property" => (self.ptr as *mut i64).write_unaligned(value),
+                kind => return Err(UnknownEnumKind(kind.to_string())),
+            }
+        }
+        Ok(())
+    }
+    /// name of the current value, `None` if there is no enum, the value isn't a valid enumerator
+    /// or the underlying property kind is unknown
+    pub fn name(&self) -> Option<String> {
+        self.enum_.as_ref()?.name_of_value(self.get().ok()?)
+    }
+}
+
+/// Underlying property kind of an enum value that isn't an integer property
+#[derive(Debug, Clone)]
+pub struct UnknownEnumKind(pub String);
+
+#[derive(Debug, Clone)]
+pub struct InterfaceValueWrapper<'a> {
+    ptr: *mut FScriptInterface,
+    _marker: PhantomData<&'a mut FScriptInterface>,
+}
+impl<'a> Pointer for InterfaceValueWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.ptr, f)
+    }
+}
+impl<'a> ArrayElement<'a> for InterfaceValueWrapper<'a> {
+    unsafe fn create(ptr: *mut c_void, prop: &PropertyWrapper<'a>) -> InterfaceValueWrapper<'a> {
+        prop.upcast::<InterfacePropertyWrapper<'a>>();
+        InterfaceValueWrapper::new(ptr as *mut FScriptInterface)
+    }
+}
+impl<'a> InterfaceValueWrapper<'a> {
+    pub unsafe fn new(ptr: *mut FScriptInterface) -> InterfaceValueWrapper<'a> {
+        assert!(!ptr.is_null());
+        InterfaceValueWrapper { ptr, _marker: PhantomData }
+    }
+    pub fn object(&self) -> Option<ObjectWrapper<'a>> {
+        unsafe { ObjectWrapper::new_nullable((*self.ptr).object) }
+    }
+    /// pointer to the interface's vtable within the object
+    pub fn interface_ptr(&self) -> *mut c_void {
+        unsafe { (*self.ptr).interface }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DelegateValueWrapper<'a> {
+    ptr: *mut FScriptDelegate,
+    _marker: PhantomData<&'a mut FScriptDelegate>,
+}
+impl<'a> Pointer for DelegateValueWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.ptr, f)
+    }
+}
+impl<'a> ArrayElement<'a> for DelegateValueWrapper<'a> {
+    unsafe fn create(ptr: *mut c_void, prop: &PropertyWrapper<'a>) -> DelegateValueWrapper<'a> {
+        prop.upcast::<DelegatePropertyWrapper<'a>>();
+        DelegateValueWrapper::new(ptr as *mut FScriptDelegate)
+    }
+}
+impl<'a> DelegateValueWrapper<'a> {
+    pub unsafe fn new(ptr: *mut FScriptDelegate) -> DelegateValueWrapper<'a> {
+        assert!(!ptr.is_null());
+        DelegateValueWrapper { ptr, _marker: PhantomData }
+    }
+    /// the bound object, `None` if the delegate is unbound or the object was destroyed
+    pub fn object(&self) -> Option<ObjectWrapper<'a>> {
+        unsafe {
+            let weak = &(*self.ptr).object;
+            if weak.object_index < 0 || weak.object_serial_number == 0 {
+                return None;
+            }
+            let item = GlobalObjectArrayWrapper::get().object_array().try_get(weak.object_index)?;
+            if item.serial_number() != weak.object_serial_number || (*item.as_ptr()).object.is_null() {
+                return None;
+            }
+            Some(item.object())
+        }
+    }
+    pub fn function_name(&self) -> String {
+        unsafe { (*self.ptr).function_name.to_string_lossy() }
+    }
+    pub fn function(&self) -> Option<FunctionWrapper<'a>> {
+        self.object()?.class().find_function(&self.function_name())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MulticastDelegateWrapper<'a> {
+    ptr: *mut FMulticastScriptDelegate,
+    _marker: PhantomData<&'a mut FMulticastScriptDelegate>,
+}
+impl<'a> Pointer for MulticastDelegateWrapper<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.ptr, f)
+    }
+}
+impl<'a> ArrayElement<'a> for MulticastDelegateWrapper<'a> {
+    unsafe fn create(ptr: *mut c_void, prop: &PropertyWrapper<'a>) -> MulticastDelegateWrapper<'a> {
+        prop.upcast::<MulticastDelegatePropertyWrapper<'a>>();
+        MulticastDelegateWrapper::new(ptr as *mut FMulticastScriptDelegate)
+    }
+}
+impl<'a> MulticastDelegateWrapper<'a> {
+    pub unsafe fn new(ptr: *mut FMulticastScriptDelegate) -> MulticastDelegateWrapper<'a> {
+        assert!(!ptr.is_null());
+        MulticastDelegateWrapper { ptr, _marker: PhantomData }
+    }
+    pub fn len(&self) -> usize {
+        unsafe { (*self.ptr).invocation_list.len() }
+    }
+    pub fn iter(&self) -> impl Iterator<Item = DelegateValueWrapper<'a>> + 'a {
+        let list = unsafe { &(*self.ptr).invocation_list };
+        let ptr = list.ptr;
+        (0..list.len()).map(move |i| unsafe { DelegateValueWrapper::new(ptr.add(i)) })
+    }
+}
+
+/// Alignment of a property that's neither a struct nor a bool
+fn primitive_alignment(property_kind: &str, size: usize) -> usize {
+    match property_kind {
+        // FName { ComparisonIndex, Number }, FWeakObjectPtr { ObjectIndex, ObjectSerialNumber },
+        // TLazyObjectPtr { FWeakObjectPtr, FGuid, TagAtLastTest } and FScriptDelegate { FWeakObjectPtr, FName }
+        // only consist of 32-bit integers, even though their size suggests a larger alignment
+        "NameProperty" | "WeakObjectProperty" | "LazyObjectProperty" | "DelegateProperty" => mem::align_of::<i32>(),
+        // everything else is a primitive or made of pointers and primitives
+        _ => {
+            let max_align = mem::align_of::<usize>().max(mem::align_of::<UeU64>());
+            1 << size.trailing_zeros().min(max_align.trailing_zeros())
+        }
+    }
+}
+
+/// Memory layout of a TSet element within its TSparseArray, see FScriptSetLayout
+#[derive(Debug, Clone, Copy)]
+struct ScriptSetLayout {
+    stride: usize,
+}
+impl ScriptSetLayout {
+    fn new(element_size: usize, element_align: usize) -> ScriptSetLayout {
+        let align_up = |val: usize, align: usize| (val + align - 1) / align * align;
+        // TSetElement { Value, HashNextId: FSetElementId(i32), HashIndex: i32 }
+        let align = element_align.max(mem::align_of::<i32>());
+        let set_element_size = align_up(align_up(element_size, mem::align_of::<i32>()) + 2 * mem::size_of::<i32>(), align);
+        // TSparseArray elements are a union with the free-list link { PrevFreeIndex: i32, NextFreeIndex: i32 }
+        ScriptSetLayout { stride: align_up(set_element_size.max(2 * mem::size_of::<i32>()), align) }
+    }
+    fn for_map(key_prop: &PropertyWrapper, value_prop: &PropertyWrapper) -> (ScriptSetLayout, usize) {
+        ScriptSetLayout::for_pair(key_prop.size(), key_prop.min_alignment(), value_prop.size(), value_prop.min_alignment())
+    }
+    /// Layout of a TMap's TSet of TPair<Key, Value> and the offset of the value within the pair
+    fn for_pair(key_size: usize, key_align: usize, value_size: usize, value_align: usize) -> (ScriptSetLayout, usize) {
+        let align_up = |val: usize, align: usize| (val + align - 1) / align * align;
+        let value_offset = align_up(key_size, value_align);
+        let align = key_align.max(value_align);
+        let pair_size = align_up(value_offset + value_size, align);
+        (ScriptSetLayout::new(pair_size, align), value_offset)
+    }
+}
+
+/// Iterates over the pointers to all allocated elements of a TSparseArray
+struct SparseArrayIter<'a> {
+    array: *mut FScriptSparseArray,
+    stride: usize,
+    index: usize,
+    _marker: PhantomData<&'a mut FScriptSparseArray>,
+}
+impl<'a> SparseArrayIter<'a> {
+    unsafe fn new(array: *mut FScriptSparseArray, stride: usize) -> SparseArrayIter<'a> {
+        assert!(!array.is_null());
+        SparseArrayIter { array, stride, index: 0, _marker: PhantomData }
+    }
+    fn is_allocated(&self, index: usize) -> bool {
+        unsafe {
+            let flags = &(*self.array).allocation_flags;
+            let words = if flags.secondary_data.is_null() { flags.inline_data.as_ptr() } else { flags.secondary_data as *const u32 };
+            *words.add(index / 32) & (1 << (index % 32)) != 0
+        }
+    }
+    fn len(&self) -> usize {
+        unsafe { (*self.array).data.len() - usize::try_from((*self.array).num_free_indices).unwrap() }
+    }
+}
+impl<'a> Iterator for SparseArrayIter<'a> {
+    type Item = *mut u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let data = unsafe { &(*self.array).data };
+        while self.index < data.len() {
+            let index = self.index;
+            self.index += 1;
+            if self.is_allocated(index) {
+                return Some(unsafe { data.ptr.add(index * self.stride) });
+            }
+        }
+        None
+    }
+}
+
+/// Wrapper for a UE-owned TSet
+#[derive(Debug)]
+pub struct SetWrapper<'a, T: ArrayElement<'a>> {
+    set: *mut FScriptSet,
+    element_prop: PropertyWrapper<'a>,
+    _marker: PhantomData<&'a mut [T]>,
+}
+impl<'a, T: ArrayElement<'a>> Pointer for SetWrapper<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.set, f)
+    }
+}
+// get rid of the implied T: Clone in derived Clone impls
+impl<'a, T: ArrayElement<'a>> Clone for SetWrapper<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            set: self.set,
+            element_prop: self.element_prop.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+impl<'a, T: ArrayElement<'a>> ArrayElement<'a> for SetWrapper<'a, T> {
+    unsafe fn create(ptr: *mut c_void, prop: &PropertyWrapper<'a>) -> SetWrapper<'a, T> {
+        let element_prop = prop.upcast::<SetPropertyWrapper<'a>>().element_prop();
+        SetWrapper::new(ptr as *mut FScriptSet, element_prop)
+    }
+}
+impl<'a, T: ArrayElement<'a>> SetWrapper<'a, T> {
+    pub unsafe fn new(set: *mut FScriptSet, element_prop: PropertyWrapper<'a>) -> SetWrapper<'a, T> {
+        assert!(!set.is_null());
+        SetWrapper { set, element_prop, _marker: PhantomData }
+    }
+    fn elements(&self) -> SparseArrayIter<'a> {
+        let layout = ScriptSetLayout::new(self.element_prop.size(), self.element_prop.min_alignment());
+        unsafe { SparseArrayIter::new(ptr::addr_of_mut!((*self.set).elements), layout.stride) }
+    }
+    pub fn len(&self) -> usize {
+        self.elements().len()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        let element_prop = self.element_prop.clone();
+        self.elements().map(move |ptr| unsafe { T::create(ptr as *mut c_void, &element_prop) })
+    }
+}
+
+/// Wrapper for a UE-owned TMap
+#[derive(Debug)]
+pub struct MapWrapper<'a, K: ArrayElement<'a>, V: ArrayElement<'a>> {
+    map: *mut FScriptSet,
+    key_prop: PropertyWrapper<'a>,
+    value_prop: PropertyWrapper<'a>,
+    _marker: PhantomData<&'a mut [(K, V)]>,
+}
+impl<'a, K: ArrayElement<'a>, V: ArrayElement<'a>> Pointer for MapWrapper<'a, K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Pointer::fmt(&self.map, f)
+    }
+}
+// get rid of the implied K: Clone, V: Clone in derived Clone impls
+impl<'a, K: ArrayElement<'a>, V: ArrayElement<'a>> Clone for MapWrapper<'a, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map,
+            key_prop: self.key_prop.clone(),
+            value_prop: self.value_prop.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+impl<'a, K: ArrayElement<'a>, V: ArrayElement<'a>> ArrayElement<'a> for MapWrapper<'a, K, V> {
+    unsafe fn create(ptr: *mut c_void, prop: &PropertyWrapper<'a>) -> MapWrapper<'a, K, V> {
+        let map_prop = prop.upcast::<MapPropertyWrapper<'a>>();
+        MapWrapper::new(ptr as *mut FScriptSet, map_prop.key_prop(), map_prop.value_prop())
+    }
+}
+impl<'a, K: ArrayElement<'a>, V: ArrayElement<'a>> MapWrapper<'a, K, V> {
+    /// TMap's memory layout is the same as the one of TSet<TPair<K, V>>
+    pub unsafe fn new(map: *mut FScriptSet, key_prop: PropertyWrapper<'a>, value_prop: PropertyWrapper<'a>) -> MapWrapper<'a, K, V> {
+        assert!(!map.is_null());
+        MapWrapper { map, key_prop, value_prop, _marker: PhantomData }
+    }
+    fn elements(&self) -> (SparseArrayIter<'a>, usize) {
+        let (layout, value_offset) = ScriptSetLayout::for_map(&self.key_prop, &self.value_prop);
+        unsafe { (SparseArrayIter::new(ptr::addr_of_mut!((*self.map).elements), layout.stride), value_offset) }
+    }
+    pub fn len(&self) -> usize {
+        self.elements().0.len()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = (K, V)> + 'a {
+        let key_prop = self.key_prop.clone();
+        let value_prop = self.value_prop.clone();
+        let (elements, value_offset) = self.elements();
+        elements.map(move |ptr| unsafe {
+            (
+                K::create(ptr as *mut c_void, &key_prop),
+                V::create(ptr.add(value_offset) as *mut c_void, &value_prop),
+            )
+        })
+    }
+}
+
 unsafe fn apply_field_info<'a>(ptr: *mut u8, info: FieldInfo<'a>) -> DynamicValue<'a> {
     assert!(!ptr.is_null());
     let value_ptr = ptr.offset(info.offset) as *mut c_void;
     DynamicValue::new(value_ptr, info.prop)
 }
 
+#[cfg(test)]
+mod tests {
+    use std::mem;
+    use super::{primitive_alignment, ScriptSetLayout};
+
+    #[test]
+    fn name_like_properties_are_4_byte_aligned() {
+        assert_eq!(primitive_alignment("NameProperty", 8), 4);
+        assert_eq!(primitive_alignment("WeakObjectProperty", 8), 4);
+        assert_eq!(primitive_alignment("LazyObjectProperty", 28), 4);
+        assert_eq!(primitive_alignment("DelegateProperty", 16), 4);
+        assert_eq!(primitive_alignment("IntProperty", 4), 4);
+        assert_eq!(primitive_alignment("ByteProperty", 1), 1);
+        assert_eq!(primitive_alignment("ObjectProperty", mem::size_of::<usize>()), mem::align_of::<usize>());
+    }
+
+    #[test]
+    fn set_and_map_layouts() {
+        // TSet<int32>: value, HashNextId, HashIndex
+        assert_eq!(ScriptSetLayout::new(4, 4).stride, 12);
+        // TSet<FName>
+        assert_eq!(ScriptSetLayout::new(8, primitive_alignment("NameProperty", 8)).stride, 16);
+        // TMap<FName, int32>
+        let (layout, value_offset) = ScriptSetLayout::for_pair(8, primitive_alignment("NameProperty", 8), 4, 4);
+        assert_eq!((layout.stride, value_offset), (20, 8));
+        // TMap<int32, UObject*>
+        let ptr = mem::size_of::<usize>();
+        let (layout, value_offset) = ScriptSetLayout::for_pair(4, 4, ptr, ptr);
+        assert_eq!((layout.stride, value_offset), (2 * ptr + 8, ptr));
+    }
+}
@@ -68,6 +68,30 @@ impl FSlateApplication {
         }
     }
 
+    pub(super) fn disable(&self) {
+        self._tick.disable();
+        self.onkeydown.disable();
+        self.onkeyup.disable();
+        self.onrawmousemove.disable();
+        self._onmousemove.disable();
+        self._onmousedown.disable();
+        self._onmousedoubleclick.disable();
+        self._onmouseup.disable();
+        self._onmousewheel.disable();
+    }
+
+    pub(super) unsafe fn remove(self) {
+        self._tick.remove();
+        self.onkeydown.remove();
+        self.onkeyup.remove();
+        self.onrawmousemove.remove();
+        self._onmousemove.remove();
+        self._onmousedown.remove();
+        self._onmousedoubleclick.remove();
+        self._onmouseup.remove();
+        self._onmousewheel.remove();
+    }
+
     fn get_this_pointer(fn_name: &str) -> *mut FSlateApplicationUE {
         let slateapp = SLATEAPP.load(Ordering::SeqCst);
         if slateapp.is_null() {
@@ -1,6 +1,6 @@
 use std::{mem, ptr, cell::Cell};
 use std::sync::{OnceLock, atomic::Ordering};
-use crate::native::{ArrayWrapper, BoolValueWrapper, ObjectIndex, ObjectWrapper, ObjectWrapperType, StructValueWrapper, UeScope};
+use crate::native::{ActorWrapper, ActorWrapperType, ArrayWrapper, BoolValueWrapper, ClassWrapper, ObjectIndex, ObjectWrapper, ObjectWrapperType, StructValueWrapper, UeScope};
 
 #[cfg(unix)] use libc::{c_void, c_int};
 use hook::{ArgsRef, IsaAbi, RawHook};
@@ -8,7 +8,7 @@ use hook::{ArgsRef, IsaAbi, RawHook};
 
 use crate::native::ue::{FName, FVector, FRotator};
 use crate::native::{APAWN_SPAWNDEFAULTCONTROLLER, AACTOR_SETACTORENABLECOLLISION, GWORLD, UWORLD_SPAWNACTOR, UWORLD_DESTROYACTOR, AMyCharacter, UGAMEPLAYSTATICS_GETACCURATEREALTIME, UMATERIALINSTANCEDYNAMIC_SETSCALARPARAMETERVALUE};
-use crate::native::character::AMyCharacterUE;
+use crate::native::character::{AMyCharacterUE, USceneComponent};
 use crate::native::gameinstance::UMyGameInstance;
 use crate::native::reflection::{AActor, UClass, UObject};
 
@@ -20,6 +20,7 @@ pub static CLOUDS_INDEX: OnceLock<ObjectIndex<ObjectWrapperType>> = OnceLock::ne
 pub static JUMP6_INDEX: OnceLock<ObjectIndex<ObjectWrapperType>> = OnceLock::new();
 pub static ENGINE_INDEX: OnceLock<ObjectIndex<ObjectWrapperType>> = OnceLock::new();
 pub static CAMERA_INDEX: OnceLock<ObjectIndex<ObjectWrapperType>> = OnceLock::new();
+pub static PLAYER_START_INDEX: OnceLock<ObjectIndex<ActorWrapperType>> = OnceLock::new();
 
 #[derive(Debug)]
 #[repr(u8)]
@@ -61,6 +62,20 @@ impl FActorSpawnParameters {
     const B_NO_FAIL: u8 = 0b0000_0010;
     const B_DEFER_CONSTRUCTION: u8 = 0b0000_0100;
     const B_ALLOW_DURING_CONSTRUCTION_SCRIPT: u8 = 0b0000_1000;
+
+    fn always_spawn() -> FActorSpawnParameters {
+        FActorSpawnParameters {
+            name: FName::NAME_None,
+            template: ptr::null(),
+            owner: ptr::null(),
+            instigator: ptr::null(),
+            override_level: ptr::null(),
+            spawn_collision_handling_override: ESpawnActorCollisionHandlingMethod::AlwaysSpawn,
+            bitfield: FActorSpawnParameters::B_NO_FAIL,
+            name_node: ESpawnActorNameMode::RequiredFatal,
+            object_flags: 0x00000000,
+        }
+    }
 }
 
 impl APawn {
@@ -140,17 +155,7 @@ impl UWorld {
         unsafe {
             let location = FVector { x, y, z };
             let rotation = FRotator { pitch, yaw, roll };
-            let spawn_parameters = FActorSpawnParameters {
-                name: FName::NAME_None,
-                template: ptr::null(),
-                owner: ptr::null(),
-                instigator: ptr::null(),
-                override_level: ptr::null(),
-                spawn_collision_handling_override: ESpawnActorCollisionHandlingMethod::AlwaysSpawn,
-                bitfield: FActorSpawnParameters::B_NO_FAIL,
-                name_node: ESpawnActorNameMode::RequiredFatal,
-                object_flags: 0x00000000,
-            };
+            let spawn_parameters = FActorSpawnParameters::always_spawn();
             let ptr = Self::spawn_actor(
                 AMyCharacter::static_class(), &location, &rotation, &spawn_parameters,
             ) as *mut AMyCharacterUE;
@@ -171,6 +176,25 @@ impl UWorld {
         }
     }
 
+    /// Spawn an actor of any class extending `Actor`, returning null if spawning failed.
+    pub fn spawn_actor_of_class(class: &ClassWrapper, location: FVector, rotation: FRotator) -> *mut AActor {
+        assert!(class.extends_from("Actor"), "can't spawn non-actor class {}", class.name());
+        let spawn_parameters = FActorSpawnParameters::always_spawn();
+        unsafe { Self::spawn_actor(class.as_ptr(), &location, &rotation, &spawn_parameters) }
+    }
+    /// Spawn a copy of `template` with all of its properties, returning null if spawning failed.
+    pub fn spawn_actor_from_template(template: &ActorWrapper, location: FVector, rotation: FRotator) -> *mut AActor {
+        let mut spawn_parameters = FActorSpawnParameters::always_spawn();
+        spawn_parameters.template = template.as_ptr();
+        unsafe { Self::spawn_actor(template.class().as_ptr(), &location, &rotation, &spawn_parameters) }
+    }
+    pub fn destroy_spawned_actor(actor: &ActorWrapper) {
+        let destroyed = unsafe { Self::destroy_actor(actor.as_ptr(), true, true) };
+        if !destroyed {
+            log!("actor {} ({:p}) not destroyed", actor.name(), actor.as_ptr());
+        }
+    }
+
     pub fn set_sun_redness(redness: f32) {
         let obj = unsafe { ObjectWrapper::new(UMyGameInstance::get_umygameinstance() as *mut UObject) };
         let keys = obj.get_field("WorldReferences")
@@ -288,6 +312,16 @@ impl UWorld {
         world_settings.get_field("bWorldGravitySet").unwrap::<BoolValueWrapper>().set(true);
         world_settings.get_field("WorldGravityZ").unwrap::<&Cell<f32>>().set(gravity);
     }
+    pub fn get_gravity() -> f32 {
+        let obj = unsafe { ObjectWrapper::new(UWorld::get_global() as *mut UObject) };
+        let world_settings = obj.get_field("PersistentLevel")
+            .field("WorldSettings").unwrap::<ObjectWrapper>();
+        if world_settings.get_field("bWorldGravitySet").unwrap::<BoolValueWrapper>().get() {
+            world_settings.get_field("WorldGravityZ").unwrap::<f32>()
+        } else {
+            world_settings.get_field("GlobalDefaultGravityZ").unwrap::<f32>()
+        }
+    }
 
     pub fn get_time_of_day() -> f32 {
         let obj = unsafe { ObjectWrapper::new(UMyGameInstance::get_umygameinstance() as *mut UObject) };
@@ -337,6 +371,33 @@ impl UWorld {
             .unwrap::<&Cell<f32>>()
             .set(kill_z);
     }
+    pub fn get_kill_z() -> f32 {
+        let obj = unsafe { ObjectWrapper::new(UWorld::get_global() as *mut UObject) };
+        obj.get_field("PersistentLevel")
+            .field("WorldSettings")
+            .field("KillZ")
+            .unwrap::<f32>()
+    }
+
+    /// Location and rotation of the `PlayerStart` the player is spawned at when starting a new game,
+    /// `None` if there is no `PlayerStart`
+    pub fn get_player_start() -> Option<(FVector, FRotator)> {
+        UeScope::with(|scope| {
+            let player_start = scope.try_get(PLAYER_START_INDEX.get()?).ok()?;
+            let (x, y, z) = player_start.absolute_location();
+            let (pitch, yaw, roll) = player_start.relative_rotation();
+            Some((FVector { x, y, z }, FRotator { pitch, yaw, roll }))
+        })
+    }
+    /// Move the `PlayerStart`, logging if there is none
+    pub fn set_player_start(location: FVector, rotation: FRotator) {
+        UeScope::with(|scope| {
+            match PLAYER_START_INDEX.get().map(|index| scope.try_get(index)) {
+                Some(Ok(player_start)) => USceneComponent::set_world_location_and_rotation(location, rotation, &player_start),
+                _ => log!("no PlayerStart found, can't move the spawn"),
+            }
+        })
+    }
 
     pub fn set_reflection_render_scale(render_scale: i32) {
         let obj = unsafe { ObjectWrapper::new(UMyGameInstance::get_umygameinstance() as *mut UObject) };
@@ -393,6 +454,12 @@ pub fn init() {
             if class_name == "jump6_C" && name != "Default__jump6_C" {
                 JUMP6_INDEX.set(scope.object_index(&object)).ok().unwrap();
             }
+            if class_name == "PlayerStart" && name != "Default__PlayerStart" {
+                let player_start: ActorWrapper = object.upcast();
+                if PLAYER_START_INDEX.set(scope.object_index(&player_start)).is_err() {
+                    log!("found multiple PlayerStarts, using the first one");
+                }
+            }
             if class_name == "GameEngine" && name != "Default__GameEngine" {
                 ENGINE_INDEX.set(scope.object_index(&object)).ok().unwrap();
             }
@@ -10,7 +10,10 @@ use winapi::um::winnt::{HANDLE, THREAD_ALL_ACCESS};
 use winapi::um::tlhelp32::{CreateToolhelp32Snapshot, TH32CS_SNAPTHREAD, THREADENTRY32, Thread32First, Thread32Next};
 use winapi::um::handleapi::{INVALID_HANDLE_VALUE, CloseHandle};
 use winapi::um::processthreadsapi::{GetCurrentThreadId, GetCurrentProcessId, OpenThread, SuspendThread, ResumeThread};
-use winapi::um::libloaderapi::GetModuleHandleA;
+use winapi::um::libloaderapi::{GetModuleHandleA, FreeLibraryAndExitThread};
+use winapi::shared::minwindef::HMODULE;
+
+static MODULE: AtomicUsize = AtomicUsize::new(0);
 
 // https://www.unknowncheats.me/forum/general-programming-and-reversing/123333-demo-pure-rust-internal-coding.html
 // Entry Point
@@ -18,11 +21,20 @@ use winapi::um::libloaderapi::GetModuleHandleA;
 #[allow(non_snake_case, unused_variables)]
 pub extern "stdcall" fn DllMain(module: u32, reason: u32, reserved: *mut c_void) {
     match reason {
-        1 => crate::initialize(),
+        1 => {
+            MODULE.store(module as usize, Ordering::SeqCst);
+            crate::initialize()
+        },
         _ => ()
     }
 }
 
+/// Unload our DLL and exit the current thread, which must not be a game thread.
+pub fn unload_library() {
+    log!("unloading rtil.dll");
+    unsafe { FreeLibraryAndExitThread(MODULE.load(Ordering::SeqCst) as HMODULE, 0) }
+}
+
 pub struct ThreadHandles(Vec<HANDLE>);
 
 impl Drop for ThreadHandles {
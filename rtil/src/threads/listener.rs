@@ -1,5 +1,7 @@
-use std::net::TcpListener;
+use std::net::{Shutdown, TcpListener, TcpStream};
 use std::io::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread::{self, JoinHandle};
 
 use crossbeam_channel::{Sender, Receiver, TryRecvError};
@@ -7,9 +9,14 @@ use crossbeam_channel::{Sender, Receiver, TryRecvError};
 use crate::threads::{stream_read, stream_write, StreamToListener, StreamToRebo, ReboToStream, ListenerToStream};
 use crate::error::Result;
 
+const ADDRESS: &str = "127.0.0.1:21337";
+
+static SHOULD_STOP: AtomicBool = AtomicBool::new(false);
+static LISTENER_THREAD: Mutex<Option<JoinHandle<Option<TcpStream>>>> = Mutex::new(None);
+
 pub fn run(stream_rebo_tx: Sender<StreamToRebo>, rebo_stream_rx: Receiver<ReboToStream>) -> Result<()> {
     log!("starting listener thread");
-    let listener = TcpListener::bind("127.0.0.1:21337")?;
+    let listener = TcpListener::bind(ADDRESS)?;
     let mut stream_rebo_tx = Some(stream_rebo_tx);
     let mut rebo_stream_rx = Some(rebo_stream_rx);
     let (mut listener_stream_tx, listener_stream_rx) = crossbeam_channel::unbounded();
@@ -17,14 +24,18 @@ pub fn run(stream_rebo_tx: Sender<StreamToRebo>, rebo_stream_rx: Receiver<ReboTo
     let (stream_listener_tx, mut stream_listener_rx) = crossbeam_channel::unbounded();
     let mut stream_listener_tx = Some(stream_listener_tx);
 
-    thread::spawn(move || {
+    let handle = thread::spawn(move || {
         let mut stream_read_thread: Option<JoinHandle<Sender<StreamToRebo>>> = None;
         let mut stream_write_thread: Option<JoinHandle<Receiver<ReboToStream>>> = None;
+        let mut current_con: Option<TcpStream> = None;
 
         // make first iteration work
         stream_listener_tx.as_ref().unwrap().send(StreamToListener::ImDead).unwrap();
 
         while let Ok((mut con, _)) = listener.accept() {
+            if SHOULD_STOP.load(Ordering::SeqCst) {
+                break;
+            }
             log!("Got new connection from {:?}", con.peer_addr());
             match stream_listener_rx.try_recv() {
                 Ok(StreamToListener::ImDead) => {}
@@ -57,9 +68,34 @@ pub fn run(stream_rebo_tx: Sender<StreamToRebo>, rebo_stream_rx: Receiver<ReboTo
             while let Ok(_) = rebo_stream_rx.as_ref().unwrap().try_recv() {}
 
             log!("Starting stream threads.");
+            current_con = Some(con.try_clone().unwrap());
             stream_read_thread = Some(stream_read::run(con.try_clone().unwrap(), stream_listener_tx.take().unwrap(), stream_rebo_tx.take().unwrap()));
             stream_write_thread = Some(stream_write::run(con, listener_stream_rx.take().unwrap(), rebo_stream_rx.take().unwrap()));
         }
+
+        log!("stopping listener thread");
+        // stream_read stops by itself after an unload request, otherwise closing our read-half makes it die
+        if let Some(con) = &current_con {
+            let _ = con.shutdown(Shutdown::Read);
+        }
+        if let Some(thread) = stream_read_thread {
+            let _ = thread.join();
+        }
+        let _ = listener_stream_tx.send(ListenerToStream::KillYourself);
+        if let Some(thread) = stream_write_thread {
+            let _ = thread.join();
+        }
+        log!("listener thread stopped");
+        current_con
     });
+    *LISTENER_THREAD.lock().unwrap() = Some(handle);
     Ok(())
 }
+
+/// Stop listening, returning the current connection to the tool if there is one.
+pub fn stop() -> Option<TcpStream> {
+    SHOULD_STOP.store(true, Ordering::SeqCst);
+    // wake up the listener thread blocking in `accept`
+    let _ = TcpStream::connect(ADDRESS);
+    LISTENER_THREAD.lock().unwrap().take()?.join().unwrap()
+}
@@ -1,3 +1,5 @@
+use std::net::TcpStream;
+
 use crate::native::Hooks;
 
 mod listener;
@@ -12,6 +14,12 @@ pub fn start(hooks: Hooks) {
     ue::run(stream_rebo_rx, rebo_stream_tx, hooks);
 }
 
+/// Stop the listener and stream threads and free the port.
+/// Returns the current connection to the tool, which can only be written to anymore.
+pub fn stop() -> Option<TcpStream> {
+    listener::stop()
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum ListenerToStream {
     KillYourself,
@@ -28,6 +36,10 @@ pub enum StreamToRebo {
     Start(String, String),
     Stop,
     WorkingDir(String),
+    /// stop rebo and unload rtil from the game
+    Unload,
+    /// dump the SDK to the given path
+    DumpSdk(String),
 }
 
 #[derive(Debug, PartialEq, Eq)]
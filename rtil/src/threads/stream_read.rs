@@ -6,7 +6,7 @@ use byteorder::{ReadBytesExt, LittleEndian};
 use crossbeam_channel::Sender;
 
 use crate::threads::{StreamToListener, StreamToRebo};
-use crate::error::Result;
+use crate::error::{Error, Result};
 
 struct StreamRead {
     con: TcpStream,
@@ -49,11 +49,22 @@ impl StreamRead {
                 log!("Got stop");
                 self.stream_rebo_tx.send(StreamToRebo::Stop).unwrap()
             }
+            2 => {
+                log!("Got unload");
+                self.stream_rebo_tx.send(StreamToRebo::Unload).unwrap();
+                // the connection is kept open to confirm the unload, but we must not read from it anymore
+                return Err(Error::StopListening);
+            }
             3 => {
                 log!("Reading working dir");
                 let path = self.read_string()?;
                 self.stream_rebo_tx.send(StreamToRebo::WorkingDir(path)).unwrap();
             }
+            4 => {
+                log!("Reading sdk dump path");
+                let path = self.read_string()?;
+                self.stream_rebo_tx.send(StreamToRebo::DumpSdk(path)).unwrap();
+            }
             255 => log!("Got Error code from client: {}", self.con.read_u8()?),
             cmd => {
                 log!("Client sent invalid command: {}", cmd);
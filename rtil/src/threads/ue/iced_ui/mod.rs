@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::{iter, mem, thread};
 use std::sync::{mpsc, Arc, Mutex, MutexGuard};
 use std::sync::mpsc::{Receiver, Sender};
+use std::thread::JoinHandle;
 use iced::{color, keyboard, Event, Length, Point};
 use iced::mouse::{Button, Interaction};
 use iced::widget::{container, mouse_area, text, Stack};
@@ -62,6 +63,7 @@ pub struct ReboUi {
     ui_event_rx: Receiver<BoundFunctionValue<()>>,
     input_event_tx: Sender<InputEvent>,
     keyboard_state: KeyboardState,
+    thread: JoinHandle<()>,
 }
 fn transparent_texture(width: u32, height: u32) -> UTexture2D {
     UTexture2D::create_with_pixelformat(&vec![0; width as usize * height as usize * 4], width.try_into().unwrap(), height.try_into().unwrap(), UiBackend::PIXEL_FORMAT)
@@ -88,7 +90,7 @@ impl ReboUi {
             input_event_rx,
             mouse_pos: Point::new(0., 0.),
         };
-        state.start_thread();
+        let thread = state.start_thread();
 
         Self {
             ue_texture,
@@ -96,9 +98,18 @@ impl ReboUi {
             ui_event_rx,
             input_event_tx,
             keyboard_state: KeyboardState::new(),
+            thread,
         }
     }
 
+    /// Stop the UI thread and free its textures.
+    pub fn stop(self) {
+        let Self { windows_tx, thread, .. } = self;
+        // dropping the sender wakes up the UI thread and makes it exit
+        drop(windows_tx);
+        thread.join().unwrap();
+    }
+
     pub fn next_ui_event(&self) -> Option<BoundFunctionValue<()>> {
         self.ui_event_rx.try_recv().ok()
     }
@@ -152,10 +163,13 @@ struct ReboUiThreadState {
     mouse_pos: Point,
 }
 impl ReboUiThreadState {
-    fn start_thread(mut self) {
+    fn start_thread(mut self) -> JoinHandle<()> {
         thread::spawn(move || {
             loop {
-                let windows = self.windows_rx.read_consume().unwrap();
+                let Ok(windows) = self.windows_rx.read_consume() else {
+                    log!("stopping UI thread");
+                    return;
+                };
 
                 // handle input events
                 for event in self.input_event_rx.try_iter() {
@@ -194,7 +208,7 @@ impl ReboUiThreadState {
                 mem::swap(&mut lock.texture, &mut self.current_ue_texture);
                 lock.interaction = interaction;
             }
-        });
+        })
     }
 }
 
@@ -1,5 +1,7 @@
+use std::ffi::c_void;
+use std::sync::Mutex;
 use crossbeam_channel::{Receiver, Sender};
-use crate::native::{ALiftBaseUE, ElementIndex, EMouseButtonsType, Hooks, try_find_element_index, UObject};
+use crate::native::{ALiftBaseUE, AMyCharacter, ElementIndex, ElementType, EMouseButtonsType, FApp, FunctionWrapper, Hooks, Overlap, StructValueWrapper, try_find_element_index, UFunction, UObject, UWorld};
 use crate::threads::{ReboToStream, StreamToRebo};
 use crate::threads::ue::iced_ui::Key;
 
@@ -11,6 +13,12 @@ enum UeEvent {
     Tick,
     ElementPressed(ElementIndex),
     ElementReleased(ElementIndex),
+    CubeCollected(ElementIndex),
+    UnderwaterChanged(bool),
+    PipeEntered(ElementIndex),
+    PipeExited(ElementIndex),
+    SpringpadLaunched(ElementIndex),
+    FellBelowKillZ,
     /// Response to `Yield` if no new event happened
     NothingHappened,
     NewGame,
@@ -23,6 +31,7 @@ enum UeEvent {
     DrawHud,
     ApplyResolutionSettings,
     AddToScreen,
+    ProcessEvent(rebo::UeFunctionCall),
 }
 #[derive(Debug, Clone, Copy)]
 enum Suspend {
@@ -39,19 +48,38 @@ pub fn run(stream_rebo_rx: Receiver<StreamToRebo>, rebo_stream_tx: Sender<ReboTo
 
 pub fn new_game() {
     log!("New Game");
+    *PIPE_ENTERED.lock().unwrap() = None;
+    rebo::arm_cluster_triggers();
     handle(UeEvent::NewGame)
 }
 
 pub fn tick() {
+    if fell_below_kill_z() {
+        handle(UeEvent::FellBelowKillZ);
+    }
     handle(UeEvent::Tick);
 }
 
+/// the player's z and z-velocity of the last tick
+static LAST_PLAYER_Z: Mutex<Option<(f32, f32)>> = Mutex::new(None);
+/// UE resets the player within the frame they fall below kill-z, so we never see them below it.
+/// Instead, we check if they would have crossed it with their location and velocity of the last frame.
+fn fell_below_kill_z() -> bool {
+    let Some(player) = AMyCharacter::try_get_player() else { return false };
+    let (_, _, z) = player.location();
+    let (_, _, vz) = player.velocity();
+    let Some((last_z, last_vz)) = LAST_PLAYER_Z.lock().unwrap().replace((z, vz)) else { return false };
+    let kill_z = UWorld::get_kill_z();
+    last_z > kill_z && last_z + last_vz * FApp::delta() as f32 <= kill_z
+}
+
 pub fn add_based_character(ptr: *mut ALiftBaseUE) {
     // TODO: remove once we added pipes to the map editor
     let element_index = match try_find_element_index(ptr as *mut UObject) {
         Some(i) => i,
         None => return,
     };
+    rebo::fire_cluster_triggers(element_index);
     handle(UeEvent::ElementPressed(element_index));
 }
 pub fn remove_based_character(ptr: *mut ALiftBaseUE) {
@@ -63,6 +91,52 @@ pub fn remove_based_character(ptr: *mut ALiftBaseUE) {
     handle(UeEvent::ElementReleased(element_index));
 }
 
+pub fn underwater_changed(underwater: bool) {
+    // don't report changes made by rebo itself, e.g. `Tas::exit_water`
+    if rebo::is_executing() {
+        return;
+    }
+    handle(UeEvent::UnderwaterChanged(underwater));
+}
+
+/// the pipe the player entered if they are currently travelling through pipes
+static PIPE_ENTERED: Mutex<Option<ElementIndex>> = Mutex::new(None);
+pub fn actor_overlap(object: *mut UObject, function: *mut UFunction, params: *mut c_void, overlap: Overlap) {
+    // don't report overlaps triggered by rebo itself, e.g. via `Tas::trigger_element`
+    if rebo::is_executing() || params.is_null() {
+        return;
+    }
+    let Some(player) = AMyCharacter::try_get_player() else { return };
+    let function = unsafe { FunctionWrapper::new(function) };
+    let params = unsafe { StructValueWrapper::new(params, function.upcast()) };
+    let other_actor = params.try_get_field("OtherActor").and_then(|actor| actor.unwrap_object());
+    if other_actor.map(|actor| actor.as_ptr()) != Some(player.as_ptr() as *mut UObject) {
+        return;
+    }
+    let Some(index) = try_find_element_index(object) else { return };
+    let mut pipe_entered = PIPE_ENTERED.lock().unwrap();
+    let event = match (index.element_type, overlap) {
+        (ElementType::Cube, Overlap::Begin) => UeEvent::CubeCollected(index),
+        (ElementType::Springpad, Overlap::Begin) => UeEvent::SpringpadLaunched(index),
+        // While travelling, the player arrives in the exit pipe's trigger and leaves the entered one.
+        // Thus, they exited once they leave another pipe's trigger than the one they entered.
+        (ElementType::Pipe, Overlap::Begin) if pipe_entered.is_none() => {
+            *pipe_entered = Some(index);
+            UeEvent::PipeEntered(index)
+        }
+        (ElementType::Pipe, Overlap::End) => match *pipe_entered {
+            Some(entered) if entered != index => {
+                *pipe_entered = None;
+                UeEvent::PipeExited(index)
+            }
+            _ => return,
+        }
+        _ => return,
+    };
+    drop(pipe_entered);
+    handle(event);
+}
+
 fn codes_to_key(key_code: i32, character_code: u32) -> Key {
     #[cfg(unix)] {
         Key::try_from_linux(key_code, character_code)
@@ -104,6 +178,14 @@ pub fn add_to_screen() {
     handle(UeEvent::AddToScreen);
 }
 
+pub fn process_event(object: *mut UObject, function: *mut UFunction, params: *mut c_void) {
+    // don't report calls made by rebo itself
+    if rebo::is_executing() {
+        return;
+    }
+    handle(UeEvent::ProcessEvent(rebo::decode_function_call(object, function, params)));
+}
+
 fn handle(event: UeEvent) {
     rebo::poll(event);
 }
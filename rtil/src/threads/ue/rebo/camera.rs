@@ -0,0 +1,250 @@
+use std::cell::Cell;
+use std::sync::Mutex;
+use crate::native::{ActorWrapper, ActorWrapperType, AMyCharacter, BoolValueWrapper, ClassWrapper, ObjectIndex, ObjectWrapper, StructValueWrapper, UObject, UWorld, UeScope, ue::{FRotator, FVector}};
+use super::rebo_init::{Location, Rotation};
+
+static CAMERA: Mutex<Option<Camera>> = Mutex::new(None);
+
+/// Units per second the free camera moves with at full input
+const DEFAULT_SPEED: f32 = 2000.;
+
+struct Camera {
+    actor: ObjectIndex<ActorWrapperType>,
+    mode: CameraMode,
+    location: Location,
+    /// player location to hold and rotation to restore while the free camera is active
+    player_location: Location,
+    player_rotation: Rotation,
+    input: CameraInput,
+    speed: f32,
+    fov: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, rebo::ExternalType)]
+pub enum CameraMode {
+    /// move freely while the player stays in place
+    Free,
+    /// circle around the player at the given distance
+    Orbit(f32),
+}
+/// Movement input of the free camera relative to its rotation, each within -1..=1
+#[derive(Debug, Clone, Copy, Default, rebo::ExternalType)]
+pub struct CameraInput {
+    forward: f32,
+    right: f32,
+    up: f32,
+}
+#[derive(Debug, Clone, rebo::ExternalType)]
+pub enum CameraError {
+    NotActive,
+    SpawnFailed,
+}
+
+/// Forward and right unit vectors of the rotation
+fn directions(rotation: Rotation) -> ((f32, f32, f32), (f32, f32, f32)) {
+    let (pitch, yaw) = (rotation.pitch.to_radians(), rotation.yaw.to_radians());
+    let forward = (pitch.cos() * yaw.cos(), pitch.cos() * yaw.sin(), pitch.sin());
+    let right = (-yaw.sin(), yaw.cos(), 0.);
+    (forward, right)
+}
+
+fn set_vector(value: StructValueWrapper, x: f32, y: f32, z: f32) {
+    value.get_field("X").unwrap::<&Cell<f32>>().set(x);
+    value.get_field("Y").unwrap::<&Cell<f32>>().set(y);
+    value.get_field("Z").unwrap::<&Cell<f32>>().set(z);
+}
+
+fn set_view_target(target: &ObjectWrapper) {
+    let controller = unsafe { ObjectWrapper::new(AMyCharacter::get_player().controller() as *mut UObject) };
+    let fun = controller.class().find_function("SetViewTargetWithBlend").unwrap();
+    let params = fun.create_argument_struct();
+    params.get_field("NewViewTarget").set_object(target);
+    unsafe { fun.call(controller.as_ptr(), &params) };
+}
+fn view_target_is(target: &ObjectWrapper) -> bool {
+    let controller = unsafe { ObjectWrapper::new(AMyCharacter::get_player().controller() as *mut UObject) };
+    let current = controller.get_field("PlayerCameraManager").field("ViewTarget").field("Target").unwrap_object();
+    current.is_some_and(|current| current.as_ptr() == target.as_ptr())
+}
+
+/// Field of view of the player's camera, which depends on the player's settings
+fn player_fov() -> f32 {
+    let controller = unsafe { ObjectWrapper::new(AMyCharacter::get_player().controller() as *mut UObject) };
+    let camera: ObjectWrapper = controller.get_field("PlayerCameraManager").unwrap();
+    let fun = camera.class().find_function("GetFOVAngle").unwrap();
+    let params = fun.create_argument_struct();
+    unsafe { fun.call(camera.as_ptr(), &params) };
+    params.get_field("ReturnValue").unwrap()
+}
+
+fn move_actor(actor: &ActorWrapper, location: Location, rotation: Rotation) {
+    let fun = actor.class().find_function("K2_SetActorLocationAndRotation").unwrap();
+    let params = fun.create_argument_struct();
+    set_vector(params.get_field("NewLocation").unwrap::<StructValueWrapper>(), location.x, location.y, location.z);
+    let rot = params.get_field("NewRotation").unwrap::<StructValueWrapper>();
+    rot.get_field("Pitch").unwrap::<&Cell<f32>>().set(rotation.pitch);
+    rot.get_field("Yaw").unwrap::<&Cell<f32>>().set(rotation.yaw);
+    rot.get_field("Roll").unwrap::<&Cell<f32>>().set(rotation.roll);
+    params.get_field("bTeleport").unwrap::<BoolValueWrapper>().set(true);
+    unsafe { fun.call(actor.as_ptr(), &params) };
+}
+
+fn set_actor_fov(actor: &ActorWrapper, fov: f32) {
+    let component: ObjectWrapper = actor.get_field("CameraComponent").unwrap();
+    component.get_field("FieldOfView").unwrap::<&Cell<f32>>().set(fov);
+    // a CameraActor letterboxes to 16:9 by default
+    if let Some(constrain) = component.try_get_field("bConstrainAspectRatio") {
+        constrain.unwrap::<BoolValueWrapper>().set(false);
+    }
+}
+
+impl Camera {
+    /// Update the camera location from the input and move the camera actor,
+    /// re-applying it as view target if the game switched back to the player
+    fn update(&mut self, delta: f32) {
+        let mut player = AMyCharacter::get_player();
+        let (pitch, yaw, roll) = player.rotation();
+        let rotation = Rotation { pitch, yaw, roll };
+        let (forward, right) = directions(rotation);
+        match self.mode {
+            CameraMode::Free => {
+                let CameraInput { forward: f, right: r, up: u } = self.input;
+                let step = self.speed * delta;
+                self.location.x += (forward.0 * f + right.0 * r) * step;
+                self.location.y += (forward.1 * f + right.1 * r) * step;
+                self.location.z += (forward.2 * f + u) * step;
+                let Location { x, y, z } = self.player_location;
+                player.set_location(x, y, z);
+                player.set_velocity(0., 0., 0.);
+            }
+            CameraMode::Orbit(distance) => {
+                let (x, y, z) = player.location();
+                self.location = Location { x: x - forward.0 * distance, y: y - forward.1 * distance, z: z - forward.2 * distance };
+            }
+        }
+        let (location, fov, index) = (self.location, self.fov, self.actor);
+        UeScope::with(move |scope| {
+            // the camera actor may have been destroyed by the game
+            let Ok(actor) = scope.try_get(index) else { return };
+            move_actor(&actor, location, rotation);
+            set_actor_fov(&actor, fov);
+            if !view_target_is(&actor) {
+                set_view_target(&actor);
+            }
+        });
+    }
+}
+
+/// Move the camera with the elapsed time of the last frame
+pub(super) fn tick(delta: f64) {
+    if let Some(camera) = CAMERA.lock().unwrap().as_mut() {
+        camera.update(delta as f32);
+    }
+}
+
+/// Switch back to the player's camera and destroy the camera actor
+pub(super) fn disable() {
+    let Some(camera) = CAMERA.lock().unwrap().take() else { return };
+    let mut player = AMyCharacter::get_player();
+    if camera.mode == CameraMode::Free {
+        let Rotation { pitch, yaw, roll } = camera.player_rotation;
+        player.set_rotation(pitch, yaw, roll);
+    }
+    UeScope::with(move |scope| {
+        let player = unsafe { ObjectWrapper::new(AMyCharacter::get_player().as_ptr() as *mut UObject) };
+        set_view_target(&player);
+        if let Ok(actor) = scope.try_get(camera.actor) {
+            UWorld::destroy_spawned_actor(&actor);
+        }
+    });
+}
+
+/// Detach the camera from the player.
+///
+/// The camera is rotated with the mouse like the player's camera.
+/// In `Free` mode it's moved with `Tas::camera_set_input` while the player is held in place,
+/// in `Orbit` mode it looks at the player from the given distance.
+#[rebo::function("Tas::camera_enable")]
+pub fn camera_enable(mode: CameraMode) -> Result<(), CameraError> {
+    let mut camera = CAMERA.lock().unwrap();
+    if let Some(camera) = camera.as_mut() {
+        if camera.mode != CameraMode::Free && mode == CameraMode::Free {
+            let player = AMyCharacter::get_player();
+            let (x, y, z) = player.location();
+            let (pitch, yaw, roll) = player.rotation();
+            camera.player_location = Location { x, y, z };
+            camera.player_rotation = Rotation { pitch, yaw, roll };
+        }
+        camera.mode = mode;
+        return Ok(());
+    }
+    let player = AMyCharacter::get_player();
+    let (x, y, z) = player.location();
+    let (pitch, yaw, roll) = player.rotation();
+    // read before the camera actor becomes the view target
+    let fov = player_fov();
+    let actor = UeScope::with(|scope| {
+        let class = scope.iter_global_object_array()
+            .map(|item| item.object())
+            .find(|object| object.name() == "CameraActor" && object.class().extends_from("Class"))?
+            .upcast::<ClassWrapper>();
+        let actor = UWorld::spawn_actor_of_class(&class, FVector { x, y, z }, FRotator { pitch, yaw, roll });
+        if actor.is_null() {
+            return None;
+        }
+        let actor = unsafe { ActorWrapper::new(actor) };
+        Some(scope.object_index(&actor))
+    }).ok_or(CameraError::SpawnFailed)?;
+    *camera = Some(Camera {
+        actor,
+        mode,
+        location: Location { x, y, z },
+        player_location: Location { x, y, z },
+        player_rotation: Rotation { pitch, yaw, roll },
+        input: CameraInput::default(),
+        speed: DEFAULT_SPEED,
+        fov,
+    });
+    camera.as_mut().unwrap().update(0.);
+    Ok(())
+}
+#[rebo::function("Tas::camera_disable")]
+pub fn camera_disable() {
+    disable();
+}
+#[rebo::function("Tas::camera_get_mode")]
+pub fn camera_get_mode() -> Option<CameraMode> {
+    CAMERA.lock().unwrap().as_ref().map(|camera| camera.mode)
+}
+#[rebo::function("Tas::camera_set_input")]
+pub fn camera_set_input(input: CameraInput) -> Result<(), CameraError> {
+    let clamp = |value: f32| value.clamp(-1., 1.);
+    let input = CameraInput { forward: clamp(input.forward), right: clamp(input.right), up: clamp(input.up) };
+    CAMERA.lock().unwrap().as_mut().ok_or(CameraError::NotActive)?.input = input;
+    Ok(())
+}
+#[rebo::function("Tas::camera_set_speed")]
+pub fn camera_set_speed(speed: f32) -> Result<(), CameraError> {
+    CAMERA.lock().unwrap().as_mut().ok_or(CameraError::NotActive)?.speed = speed;
+    Ok(())
+}
+#[rebo::function("Tas::camera_get_location")]
+pub fn camera_get_location() -> Result<Location, CameraError> {
+    Ok(CAMERA.lock().unwrap().as_ref().ok_or(CameraError::NotActive)?.location)
+}
+/// Teleport the free camera, has no effect in `Orbit` mode
+#[rebo::function("Tas::camera_set_location")]
+pub fn camera_set_location(location: Location) -> Result<(), CameraError> {
+    CAMERA.lock().unwrap().as_mut().ok_or(CameraError::NotActive)?.location = location;
+    Ok(())
+}
+#[rebo::function("Tas::camera_get_fov")]
+pub fn camera_get_fov() -> Result<f32, CameraError> {
+    Ok(CAMERA.lock().unwrap().as_ref().ok_or(CameraError::NotActive)?.fov)
+}
+/// Set the horizontal field of view in degrees, clamped to 5..=170
+#[rebo::function("Tas::camera_set_fov")]
+pub fn camera_set_fov(fov: f32) -> Result<(), CameraError> {
+    CAMERA.lock().unwrap().as_mut().ok_or(CameraError::NotActive)?.fov = fov.clamp(5., 170.);
+    Ok(())
+}
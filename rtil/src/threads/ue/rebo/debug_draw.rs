@@ -0,0 +1,168 @@
+use std::sync::Mutex;
+use crate::native::{AMyCharacter, AMyHud, HudLine, ObjectWrapper, StructValueWrapper, UObject};
+use super::rebo_init::{Bounds, Color, Vector};
+use super::trail;
+
+static DRAWS: Mutex<Vec<WorldDraw>> = Mutex::new(Vec::new());
+
+/// Distance in front of the camera points are clipped at, closer points project to garbage
+const NEAR_PLANE: f32 = 10.;
+/// Line segments per circle of a sphere
+const SPHERE_SEGMENTS: usize = 24;
+
+struct WorldDraw {
+    shape: Shape,
+    color: Color,
+    /// HUD frames left to draw this for
+    frames: u32,
+}
+enum Shape {
+    Lines(Vec<(Vector, Vector)>, f32),
+    Text(String, Vector, f32),
+}
+
+/// Camera location and forward direction of the current frame, used to clip against the near plane
+pub(super) struct View {
+    location: Vector,
+    forward: Vector,
+}
+
+impl View {
+    pub(super) fn current() -> View {
+        let character = unsafe { ObjectWrapper::new(AMyCharacter::get_player().as_ptr() as *mut UObject) };
+        let camera: ObjectWrapper = character.get_field("Controller").field("PlayerCameraManager").unwrap();
+        let call = |name: &str, fields: [&str; 3]| -> [f32; 3] {
+            let fun = camera.class().find_function(name).unwrap();
+            let params = fun.create_argument_struct();
+            unsafe { fun.call(camera.as_ptr(), &params) };
+            let value: StructValueWrapper = params.get_field("ReturnValue").unwrap();
+            fields.map(|field| value.get_field(field).unwrap())
+        };
+        let location = call("GetCameraLocation", ["X", "Y", "Z"]);
+        let rotation = call("GetCameraRotation", ["Pitch", "Yaw", "Roll"]);
+        let (pitch, yaw) = (rotation[0].to_radians(), rotation[1].to_radians());
+        View {
+            location: Vector { x: location[0], y: location[1], z: location[2] },
+            forward: Vector { x: pitch.cos() * yaw.cos(), y: pitch.cos() * yaw.sin(), z: pitch.sin() },
+        }
+    }
+
+    /// Signed distance of the point in front of the near plane
+    fn depth(&self, p: Vector) -> f32 {
+        (p.x - self.location.x) * self.forward.x
+            + (p.y - self.location.y) * self.forward.y
+            + (p.z - self.location.z) * self.forward.z
+            - NEAR_PLANE
+    }
+
+    /// Screen position of the point, `None` if it's behind the near plane
+    pub(super) fn project_point(&self, p: Vector) -> Option<(f32, f32)> {
+        if self.depth(p) < 0. {
+            return None;
+        }
+        let (x, y, _) = AMyHud::project(p.x, p.y, p.z);
+        Some((x, y))
+    }
+
+    /// Screen positions of the part of the line in front of the near plane, `None` if it's completely behind
+    pub(super) fn project_line(&self, mut start: Vector, mut end: Vector) -> Option<((f32, f32), (f32, f32))> {
+        let (ds, de) = (self.depth(start), self.depth(end));
+        if ds < 0. && de < 0. {
+            return None;
+        }
+        if ds < 0. || de < 0. {
+            let t = ds / (ds - de);
+            let cut = Vector { x: start.x + (end.x - start.x) * t, y: start.y + (end.y - start.y) * t, z: start.z + (end.z - start.z) * t };
+            if ds < 0. { start = cut } else { end = cut }
+        }
+        let (sx, sy, _) = AMyHud::project(start.x, start.y, start.z);
+        let (ex, ey, _) = AMyHud::project(end.x, end.y, end.z);
+        Some(((sx, sy), (ex, ey)))
+    }
+}
+
+/// Draw all persistent world-space draws together with the trail overlay, must be called while drawing the HUD.
+///
+/// All lines are drawn in a single batch, text is drawn on top of them.
+pub(super) fn draw() {
+    let mut draws = DRAWS.lock().unwrap();
+    if draws.is_empty() && !trail::is_active() {
+        return;
+    }
+    let view = View::current();
+    let mut lines = Vec::new();
+    for draw in draws.iter() {
+        let Shape::Lines(shape_lines, thickness) = &draw.shape else { continue };
+        let color = (draw.color.red, draw.color.green, draw.color.blue, draw.color.alpha);
+        lines.extend(shape_lines.iter()
+            .filter_map(|&(start, end)| view.project_line(start, end))
+            .map(|(start, end)| HudLine { start, end, color, thickness: *thickness }));
+    }
+    trail::lines(&view, &mut lines);
+    AMyHud::draw_lines(&lines);
+    for draw in draws.iter_mut() {
+        if let Shape::Text(text, location, scale) = &draw.shape {
+            if let Some((x, y)) = view.project_point(*location) {
+                let color = (draw.color.red, draw.color.green, draw.color.blue, draw.color.alpha);
+                let (width, height) = AMyHud::get_text_size(text.as_str(), *scale);
+                AMyHud::draw_text(text.as_str(), color, x - width / 2., y - height / 2., *scale, false);
+            }
+        }
+        draw.frames -= 1;
+    }
+    draws.retain(|draw| draw.frames > 0);
+}
+
+/// Remove all world-space draws
+pub(super) fn clear() {
+    DRAWS.lock().unwrap().clear();
+}
+
+fn add(shape: Shape, color: Color, frames: u32) {
+    DRAWS.lock().unwrap().push(WorldDraw { shape, color, frames: frames.max(1) });
+}
+
+/// Draw a line in the world for the next `frames` frames
+#[rebo::function("Tas::draw_world_line")]
+pub fn draw_world_line(start: Vector, end: Vector, color: Color, thickness: f32, frames: u32) {
+    add(Shape::Lines(vec![(start, end)], thickness), color, frames);
+}
+/// Draw the edges of an axis-aligned box in the world for the next `frames` frames
+#[rebo::function("Tas::draw_world_box")]
+pub fn draw_world_box(bounds: Bounds, color: Color, thickness: f32, frames: u32) {
+    let corner = |i: usize| Vector {
+        x: bounds.originx + if i & 1 == 0 { -bounds.extentx } else { bounds.extentx },
+        y: bounds.originy + if i & 2 == 0 { -bounds.extenty } else { bounds.extenty },
+        z: bounds.originz + if i & 4 == 0 { -bounds.extentz } else { bounds.extentz },
+    };
+    // corners differing in exactly one axis share an edge
+    let lines = (0..8).flat_map(|i| [1, 2, 4].into_iter().filter(move |bit| i & bit == 0).map(move |bit| (i, i | bit)))
+        .map(|(a, b)| (corner(a), corner(b)))
+        .collect();
+    add(Shape::Lines(lines, thickness), color, frames);
+}
+/// Draw text centered at a point in the world for the next `frames` frames
+#[rebo::function("Tas::draw_world_text")]
+pub fn draw_world_text(text: String, location: Vector, color: Color, scale: f32, frames: u32) {
+    add(Shape::Text(text, location, scale), color, frames);
+}
+/// Draw a sphere as three axis-aligned circles in the world for the next `frames` frames
+#[rebo::function("Tas::draw_world_sphere")]
+pub fn draw_world_sphere(center: Vector, radius: f32, color: Color, thickness: f32, frames: u32) {
+    let point = |axis: usize, i: usize| {
+        let angle = i as f32 / SPHERE_SEGMENTS as f32 * std::f32::consts::TAU;
+        let (a, b) = (angle.cos() * radius, angle.sin() * radius);
+        match axis {
+            0 => Vector { x: center.x + a, y: center.y + b, z: center.z },
+            1 => Vector { x: center.x + a, y: center.y, z: center.z + b },
+            _ => Vector { x: center.x, y: center.y + a, z: center.z + b },
+        }
+    };
+    let lines = (0..3).flat_map(|axis| (0..SPHERE_SEGMENTS).map(move |i| (point(axis, i), point(axis, i + 1))))
+        .collect();
+    add(Shape::Lines(lines, thickness), color, frames);
+}
+#[rebo::function("Tas::clear_world_draws")]
+pub fn clear_world_draws() {
+    clear();
+}
@@ -0,0 +1,256 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use crate::native::{AActor, AMyCharacter, AMyHud, UWorld};
+use super::rebo_init::{self, Color, Location, Rotation};
+
+static GHOSTS: Mutex<Ghosts> = Mutex::new(Ghosts { ghosts: BTreeMap::new(), next_id: 0 });
+
+struct Ghosts {
+    ghosts: BTreeMap<u32, Ghost>,
+    next_id: u32,
+}
+struct Ghost {
+    name: String,
+    display: GhostDisplay,
+    pawn: Option<AMyCharacter>,
+    /// (time since the start of the recording, location, rotation) of each frame
+    frames: Vec<(f64, Location, Rotation)>,
+    /// time since the ghost was (re)started
+    time: f64,
+    /// index of the frame before `time`
+    frame: usize,
+    location: Location,
+    rotation: Rotation,
+    /// frame the ghost was closest to the player at the last split, splits only search forward from it
+    split_frame: usize,
+    splits: Vec<GhostSplit>,
+}
+
+#[derive(Debug, Clone, Copy, rebo::ExternalType)]
+pub enum GhostDisplay {
+    /// a pawn like the ones of other multiplayer players
+    Pawn,
+    /// an outline drawn on the HUD
+    Marker(Color),
+}
+#[derive(Debug, Clone, rebo::ExternalType)]
+pub enum GhostError {
+    /// recording name
+    RecordingNotFound(String),
+    /// recording name
+    EmptyRecording(String),
+    InvalidGhostId,
+}
+#[derive(Debug, Clone, Copy, rebo::ExternalType)]
+pub struct GhostSplit {
+    /// level the live player reached
+    level: i32,
+    /// seconds the live player is behind the ghost, negative if ahead
+    delta: f64,
+}
+#[derive(Debug, Clone, rebo::ExternalType)]
+pub struct GhostInfo {
+    id: u32,
+    name: String,
+    location: Location,
+    rotation: Rotation,
+    /// whether the ghost reached the end of its recording
+    finished: bool,
+    splits: Vec<GhostSplit>,
+}
+
+impl Ghost {
+    fn finished(&self) -> bool {
+        self.frame + 1 >= self.frames.len()
+    }
+    fn advance(&mut self, delta: f64) {
+        self.time += delta;
+        while self.frame + 1 < self.frames.len() && self.frames[self.frame + 1].0 <= self.time {
+            self.frame += 1;
+        }
+        let (start_time, start_loc, start_rot) = self.frames[self.frame];
+        let Some(&(end_time, end_loc, end_rot)) = self.frames.get(self.frame + 1) else {
+            (self.location, self.rotation) = (start_loc, start_rot);
+            return;
+        };
+        let t = ((self.time - start_time) / (end_time - start_time)).clamp(0., 1.) as f32;
+        let lerp = |a: f32, b: f32| a + (b - a) * t;
+        // rotate along the shorter direction
+        let lerp_angle = |a: f32, b: f32| a + ((b - a + 540.) % 360. - 180.) * t;
+        self.location = Location { x: lerp(start_loc.x, end_loc.x), y: lerp(start_loc.y, end_loc.y), z: lerp(start_loc.z, end_loc.z) };
+        self.rotation = Rotation {
+            pitch: lerp_angle(start_rot.pitch, end_rot.pitch),
+            yaw: lerp_angle(start_rot.yaw, end_rot.yaw),
+            roll: lerp_angle(start_rot.roll, end_rot.roll),
+        };
+    }
+    fn update_pawn(&mut self) {
+        if let Some(pawn) = &mut self.pawn {
+            let Location { x, y, z } = self.location;
+            let Rotation { pitch, yaw, roll } = self.rotation;
+            pawn.set_location(x, y, z);
+            pawn.set_velocity(0., 0., 0.);
+            pawn.set_rotation(pitch, yaw, roll);
+        }
+    }
+    fn restart(&mut self) {
+        self.time = 0.;
+        self.frame = 0;
+        self.split_frame = 0;
+        self.splits.clear();
+        self.advance(0.);
+        self.update_pawn();
+    }
+    fn info(&self, id: u32) -> GhostInfo {
+        GhostInfo {
+            id,
+            name: self.name.clone(),
+            location: self.location,
+            rotation: self.rotation,
+            finished: self.finished(),
+            splits: self.splits.clone(),
+        }
+    }
+}
+
+/// Advance all ghosts by the elapsed time of the last frame
+pub(super) fn tick(delta: f64) {
+    for ghost in GHOSTS.lock().unwrap().ghosts.values_mut() {
+        ghost.advance(delta);
+        ghost.update_pawn();
+    }
+}
+
+/// Restart all ghosts from the beginning of their recording
+pub(super) fn restart_all() {
+    for ghost in GHOSTS.lock().unwrap().ghosts.values_mut() {
+        ghost.restart();
+    }
+}
+
+/// Record the time delta of the live player at `location` reaching `level` against each ghost.
+///
+/// The ghost's time is the one of the frame closest to the player's location.
+pub(super) fn split(level: i32, location: Location) {
+    let distance = |loc: &Location| (loc.x - location.x).powi(2) + (loc.y - location.y).powi(2) + (loc.z - location.z).powi(2);
+    for ghost in GHOSTS.lock().unwrap().ghosts.values_mut() {
+        let (index, &(ghost_time, _, _)) = ghost.frames.iter().enumerate()
+            .skip(ghost.split_frame)
+            .min_by(|(_, (_, a, _)), (_, (_, b, _))| distance(a).total_cmp(&distance(b)))
+            .unwrap();
+        ghost.split_frame = index;
+        ghost.splits.push(GhostSplit { level, delta: ghost.time - ghost_time });
+    }
+}
+
+/// Draw the outlines and names of all marker ghosts, must be called while drawing the HUD
+pub(super) fn draw_markers() {
+    for ghost in GHOSTS.lock().unwrap().ghosts.values() {
+        let GhostDisplay::Marker(color) = ghost.display else { continue };
+        let color = (color.red, color.green, color.blue, color.alpha);
+        let Location { x, y, z } = ghost.location;
+        // same outline as the pawns of multiplayer players
+        let z = z - 100.;
+        let outlines = [
+            [(x - 50., y, z), (x + 50., y, z), (x + 50., y, z + 200.), (x - 50., y, z + 200.)],
+            [(x, y - 50., z), (x, y + 50., z), (x, y + 50., z + 200.), (x, y - 50., z + 200.)],
+        ];
+        for outline in outlines {
+            let projected = outline.map(|(x, y, z)| AMyHud::project(x, y, z));
+            for i in 0..4 {
+                let (sx, sy, sz) = projected[i];
+                let (ex, ey, ez) = projected[(i + 1) % 4];
+                if sz > 0. && ez > 0. {
+                    AMyHud::draw_line(sx, sy, ex, ey, color, 3.);
+                }
+            }
+        }
+        let (tx, ty, tz) = AMyHud::project(x, y, z + 200.);
+        if tz > 0. {
+            let (width, height) = AMyHud::get_text_size(ghost.name.as_str(), 1.);
+            AMyHud::draw_text(ghost.name.as_str(), color, tx - width / 2., ty - height, 1., false);
+        }
+    }
+}
+
+/// Remove all ghosts and destroy their pawns
+pub(super) fn remove_all() {
+    let mut ghosts = GHOSTS.lock().unwrap();
+    for (_id, ghost) in std::mem::take(&mut ghosts.ghosts) {
+        if let Some(pawn) = ghost.pawn {
+            UWorld::destroy_amycharaccter(pawn);
+        }
+    }
+    ghosts.next_id = 0;
+}
+
+/// Load a recording as ghost, which starts right away and is restarted on every new game
+#[rebo::function("Tas::ghost_load")]
+pub fn ghost_load(recording: String, display: GhostDisplay) -> Result<u32, GhostError> {
+    let frames = rebo_init::read_recording(&recording)
+        .ok_or_else(|| GhostError::RecordingNotFound(recording.clone()))?
+        .frames;
+    if frames.is_empty() {
+        return Err(GhostError::EmptyRecording(recording));
+    }
+    let mut time = 0.;
+    let frames: Vec<_> = frames.into_iter().enumerate().map(|(i, frame)| {
+        // the delta of a frame is the time since the previous one
+        if i > 0 {
+            time += frame.delta;
+        }
+        (time, frame.location, frame.rotation)
+    }).collect();
+    let (_, location, rotation) = frames[0];
+    let pawn = match display {
+        GhostDisplay::Pawn => {
+            let pawn = UWorld::spawn_amycharacter(location.x, location.y, location.z, rotation.pitch, rotation.yaw, rotation.roll);
+            // don't block the live player
+            AActor::set_actor_enable_collision(pawn.as_ptr() as *const AActor, false);
+            Some(pawn)
+        }
+        GhostDisplay::Marker(_) => None,
+    };
+    let mut ghost = Ghost {
+        name: recording,
+        display,
+        pawn,
+        frames,
+        time: 0.,
+        frame: 0,
+        location,
+        rotation,
+        split_frame: 0,
+        splits: Vec::new(),
+    };
+    ghost.restart();
+    let mut ghosts = GHOSTS.lock().unwrap();
+    let id = ghosts.next_id;
+    ghosts.next_id += 1;
+    ghosts.ghosts.insert(id, ghost);
+    Ok(id)
+}
+#[rebo::function("Tas::ghost_remove")]
+pub fn ghost_remove(id: u32) -> Result<(), GhostError> {
+    let ghost = GHOSTS.lock().unwrap().ghosts.remove(&id).ok_or(GhostError::InvalidGhostId)?;
+    if let Some(pawn) = ghost.pawn {
+        UWorld::destroy_amycharaccter(pawn);
+    }
+    Ok(())
+}
+#[rebo::function("Tas::ghost_remove_all")]
+pub fn ghost_remove_all() {
+    remove_all();
+}
+#[rebo::function("Tas::ghost_restart_all")]
+pub fn ghost_restart_all() {
+    restart_all();
+}
+#[rebo::function("Tas::ghost_info")]
+pub fn ghost_info(id: u32) -> Result<GhostInfo, GhostError> {
+    GHOSTS.lock().unwrap().ghosts.get(&id).map(|ghost| ghost.info(id)).ok_or(GhostError::InvalidGhostId)
+}
+#[rebo::function("Tas::ghost_list")]
+pub fn ghost_list() -> Vec<GhostInfo> {
+    GHOSTS.lock().unwrap().ghosts.iter().map(|(&id, ghost)| ghost.info(id)).collect()
+}
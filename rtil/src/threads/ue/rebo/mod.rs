@@ -13,12 +13,20 @@ use websocket::sync::Client;
 use websocket::stream::sync::NetworkStream;
 
 use crate::threads::{ReboToStream, StreamToRebo};
-use crate::native::{AMyCharacter, FPlatformMisc, Hooks, UTexture2D, UWorld, REBO_DOESNT_START_SEMAPHORE};
+use crate::native::{AMyCharacter, ActorWrapperType, FPlatformMisc, Hooks, ObjectIndex, UTexture2D, UWorld, UeScope, REBO_DOESNT_START_SEMAPHORE, clear_process_event_filters};
 use crate::threads::ue::{Suspend, UeEvent};
 use crate::threads::ue::iced_ui::ReboUi;
 
 mod rebo_init;
 mod livesplit;
+mod reflection;
+mod ghost;
+mod camera;
+mod debug_draw;
+mod trail;
+
+pub(super) use reflection::{UeFunctionCall, decode_function_call};
+pub(super) use rebo_init::{arm_cluster_triggers, fire_cluster_triggers};
 
 type Coroutine = corosensei::Coroutine<UeEvent, Suspend, ()>;
 
@@ -35,6 +43,8 @@ struct State {
 
     is_semaphore_acquired: bool,
     event_queue: VecDeque<UeEvent>,
+    /// set if the tool requested an unload while rebo was running
+    unload_requested: bool,
 
     new_version_string: Option<String>,
     delta: Option<f64>,
@@ -46,6 +56,8 @@ struct State {
     local_time_offset: i32,
     pawns: HashMap<u32, AMyCharacter>,
     pawn_id: u32,
+    actors: HashMap<u32, ObjectIndex<ActorWrapperType>>,
+    actor_id: u32,
     minimap_texture: Option<UTexture2D>,
     minimap_image: RgbaImage,
     player_minimap_image: RgbaImage,
@@ -53,6 +65,11 @@ struct State {
     player_minimap_textures: HashMap<Rgba<u8>, UTexture2D>,
 }
 
+/// Whether we are currently executing within the rebo coroutine
+pub(super) fn is_executing() -> bool {
+    COROUTINE.with(|co| co.try_borrow_mut().is_err())
+}
+
 pub(super) fn poll(event: UeEvent) {
     // check if we have acquired the semaphore
     {
@@ -117,7 +134,13 @@ pub(super) fn poll(event: UeEvent) {
         });
         match should_return {
             ShouldReturn::No => (),
-            ShouldReturn::CleanupAndYes => { cleanup_after_rebo(); return },
+            ShouldReturn::CleanupAndYes => {
+                cleanup_after_rebo();
+                if STATE.lock().unwrap().as_ref().unwrap().unload_requested {
+                    unload();
+                }
+                return
+            },
             ShouldReturn::Yes => return,
         }
     }
@@ -152,6 +175,7 @@ pub fn init(stream_rebo_rx: Receiver<StreamToRebo>, rebo_stream_tx: Sender<ReboT
         ui: ReboUi::start(),
         is_semaphore_acquired: false,
         event_queue: VecDeque::new(),
+        unload_requested: false,
         new_version_string: new_version.clone(),
         delta: None,
         stream_rebo_rx,
@@ -162,6 +186,8 @@ pub fn init(stream_rebo_rx: Receiver<StreamToRebo>, rebo_stream_tx: Sender<ReboT
         local_time_offset: 0,
         pawns: HashMap::new(),
         pawn_id: 0,
+        actors: HashMap::new(),
+        actor_id: 0,
         minimap_texture: None,
         minimap_image,
         player_minimap_image,
@@ -174,13 +200,20 @@ fn poll_tool() -> Option<Coroutine> {
         // can't use while let because of borrow extension of the lock
         match msg {
             StreamToRebo::Stop => {},
+            StreamToRebo::Unload => {
+                unload();
+                return None;
+            }
             StreamToRebo::WorkingDir(dir) => {
                 log!("Set working dir");
                 STATE.lock().unwrap().as_mut().unwrap().working_dir = Some(dir);
                 log!("Working dir set");
             }
+            StreamToRebo::DumpSdk(path) => rebo_init::dump_sdk_for_tool(path),
             StreamToRebo::Start(filename, code) => {
                 log!("Starting rebo...");
+                // report the ProcessEvent-based events while the script runs
+                STATE.lock().unwrap().as_ref().unwrap().hooks.uobject_processevent.enable();
                 return Some(Coroutine::new(|yielder, _| {
                     YIELDER.with(|y| y.set(yielder as *const _));
                     let rebo_stream_tx = STATE.lock().unwrap().as_ref().unwrap().rebo_stream_tx.clone();
@@ -204,11 +237,26 @@ fn cleanup_after_rebo() {
     COROUTINE.with(|co| *co.borrow_mut() = None);
     state.event_queue.clear();
     state.delta = None;
+    clear_process_event_filters();
+    state.hooks.uobject_processevent.disable();
     drop(state.websocket.take());
     for (_id, my_character) in state.pawns.drain() {
         UWorld::destroy_amycharaccter(my_character);
     }
     state.pawn_id = 0;
+    ghost::remove_all();
+    camera::disable();
+    debug_draw::clear();
+    trail::disable();
+    UeScope::with(|scope| {
+        for (_id, actor) in state.actors.drain() {
+            // the actor may have already been destroyed by the game, e.g. by a kill volume
+            if let Ok(actor) = scope.try_get(actor) {
+                UWorld::destroy_spawned_actor(&actor);
+            }
+        }
+    });
+    state.actor_id = 0;
     for key in state.pressed_keys.drain() {
         state.hooks.fslateapplication.release_key(key, key as u32, false);
     }
@@ -217,6 +265,23 @@ fn cleanup_after_rebo() {
     log!("Cleanup finished.");
 }
 
+/// Shut down rtil such that a new version can be injected into the running game.
+///
+/// Must only be called while rebo isn't running.
+fn unload() {
+    log!("Unloading rtil...");
+    // further events will be ignored
+    let state = STATE.lock().unwrap().take().unwrap();
+    let State { hooks, ui, minimap_texture, player_minimap_textures, .. } = state;
+    ui.stop();
+    drop(minimap_texture);
+    drop(player_minimap_textures);
+    // the listener's stream threads still send to our channels, which we keep until they are stopped
+    let con = crate::threads::stop();
+    crate::deinitialize(hooks, con);
+    log!("Unloading finished.");
+}
+
 fn check_for_new_version() -> Option<String> {
     const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
     let agent: ureq::Agent = ureq::Agent::config_builder()
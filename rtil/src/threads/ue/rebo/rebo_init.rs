@@ -3,7 +3,8 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io::{ErrorKind, Write};
 use std::ops::Deref;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use std::time::Duration;
 use crossbeam_channel::{Sender, TryRecvError};
 use image::Rgba;
@@ -11,7 +12,7 @@ use rebo::{DisplayValue, ExecError, IncludeConfig, Map, Output, ReboConfig, Span
 use itertools::Itertools;
 use once_cell::sync::Lazy;
 use websocket::{ClientBuilder, Message, OwnedMessage, WebSocketError};
-use crate::native::{character::USceneComponent, try_find_element_index, ue::FVector, AActor, ALiftBaseUE, AMyCharacter, AMyHud, ActorWrapper, EBlendMode, FApp, FViewport, KismetSystemLibrary, Level, LevelState, LevelWrapper, ObjectIndex, ObjectWrapper, UGameplayStatics, UMyGameInstance, UObject, UTexture2D, UWorld, UeObjectWrapperType, UeScope, LEVELS};
+use crate::native::{self, character::USceneComponent, try_find_element_index, ue::FVector, AActor, ALiftBaseUE, AMyCharacter, AMyHud, ActorWrapper, ClassWrapper, EBlendMode, EDrawDebugTraceType, FHitResult, FApp, FViewport, KismetSystemLibrary, Level, LevelState, LevelWrapper, ObjectIndex, ObjectWrapper, UGameplayStatics, UMyGameInstance, UObject, UTexture2D, UWorld, UeObjectWrapperType, UeScope, LEVELS};
 use protocol::{Request, Response};
 use crate::threads::{ReboToStream, StreamToRebo};
 use super::{STATE, livesplit::{Game, NewGameGlitch, SplitsSaveError, SplitsLoadError}};
@@ -20,7 +21,7 @@ use crate::threads::ue::{Suspend, UeEvent, rebo::YIELDER};
 use crate::native::{ElementIndex, ElementType, ue::{FRotator, FLinearColor}, UEngine, TimeOfDay, UWidgetBlueprintLibrary};
 use opener;
 use chrono::{DateTime, Local};
-use crate::threads::ue::rebo::livesplit;
+use crate::threads::ue::rebo::{camera, debug_draw, ghost, livesplit, trail, reflection::{self, UeFunctionCall}};
 use crate::threads::ue::iced_ui::Clipboard;
 use crate::threads::ue::iced_ui::rebo_elements::{IcedButton, IcedColumn, IcedElement, IcedRow, IcedText, IcedWindow};
 
@@ -64,6 +65,15 @@ pub fn create_config(rebo_stream_tx: Sender<ReboToStream>) -> ReboConfig {
         .add_function(set_movement_mode)
         .add_function(get_max_fly_speed)
         .add_function(set_max_fly_speed)
+        .add_function(get_movement_params)
+        .add_function(set_movement_params)
+        .add_function(get_floor)
+        .add_function(ghost::ghost_load)
+        .add_function(ghost::ghost_remove)
+        .add_function(ghost::ghost_remove_all)
+        .add_function(ghost::ghost_restart_all)
+        .add_function(ghost::ghost_info)
+        .add_function(ghost::ghost_list)
         .add_function(get_max_walk_speed)
         .add_function(get_base_speed)
         .add_function(get_max_bonus_speed)
@@ -79,12 +89,26 @@ pub fn create_config(rebo_stream_tx: Sender<ReboToStream>) -> ReboConfig {
         .add_function(player_minimap_size)
         .add_function(minimap_size)
         .add_function(project)
+        .add_function(debug_draw::draw_world_line)
+        .add_function(debug_draw::draw_world_box)
+        .add_function(debug_draw::draw_world_text)
+        .add_function(debug_draw::draw_world_sphere)
+        .add_function(debug_draw::clear_world_draws)
+        .add_function(trail::trail_enable)
+        .add_function(trail::trail_disable)
+        .add_function(trail::trail_clear)
+        .add_function(trail::trajectory_enable)
+        .add_function(trail::trajectory_disable)
         .add_function(get_viewport_size)
         .add_function(get_text_size)
         .add_function(spawn_pawn)
+        .add_function(spawn_actor)
+        .add_function(destroy_actor)
         .add_function(destroy_pawn)
         .add_function(move_pawn)
         .add_function(set_pawn_velocity)
+        .add_function(get_pawn_movement_params)
+        .add_function(set_pawn_movement_params)
         .add_function(pawn_location)
         .add_function(connect_to_server)
         .add_function(disconnect_from_server)
@@ -107,6 +131,10 @@ pub fn create_config(rebo_stream_tx: Sender<ReboToStream>) -> ReboConfig {
         .add_function(set_clipboard)
         .add_function(show_hud)
         .add_function(set_all_cluster_speeds)
+        .add_function(cluster_raise)
+        .add_function(cluster_lower)
+        .add_function(cluster_set_frozen)
+        .add_function(cluster_rise_progress)
         .add_function(list_maps)
         .add_function(load_map)
         .add_function(save_map)
@@ -115,7 +143,15 @@ pub fn create_config(rebo_stream_tx: Sender<ReboToStream>) -> ReboConfig {
         .add_function(original_map)
         .add_function(apply_map)
         .add_function(apply_map_cluster_speeds)
+        .add_function(map_begin_edit)
+        .add_function(map_commit)
+        .add_function(map_undo)
+        .add_function(map_redo)
         .add_function(get_looked_at_element_index)
+        .add_function(get_element_state)
+        .add_function(set_element_state)
+        .add_function(line_trace)
+        .add_function(capsule_trace)
         .add_function(get_element_bounds)
         .add_function(enable_collision)
         .add_function(disable_collision)
@@ -146,6 +182,15 @@ pub fn create_config(rebo_stream_tx: Sender<ReboToStream>) -> ReboConfig {
         .add_function(set_reticle_height)
         .add_function(get_camera_mode)
         .add_function(set_camera_mode)
+        .add_function(camera::camera_enable)
+        .add_function(camera::camera_disable)
+        .add_function(camera::camera_get_mode)
+        .add_function(camera::camera_set_input)
+        .add_function(camera::camera_set_speed)
+        .add_function(camera::camera_get_location)
+        .add_function(camera::camera_set_location)
+        .add_function(camera::camera_get_fov)
+        .add_function(camera::camera_set_fov)
         .add_function(livesplit::livesplit_start)
         .add_function(livesplit::livesplit_split)
         .add_function(livesplit::livesplit_reset)
@@ -182,10 +227,21 @@ pub fn create_config(rebo_stream_tx: Sender<ReboToStream>) -> ReboConfig {
         .add_function(set_input_mode_game_only)
         .add_function(set_input_mode_ui_only)
         .add_function(flush_pressed_keys)
+        .add_function(dump_sdk)
+        .add_function(reflection::find_objects)
+        .add_function(reflection::get_object_name)
+        .add_function(reflection::get_object_class_name)
+        .add_function(reflection::get_property)
+        .add_function(reflection::set_property)
+        .add_function(reflection::call_function)
+        .add_function(reflection::add_ue_event_filter)
+        .add_function(reflection::clear_ue_event_filters)
         .add_external_type(Location)
         .add_external_type(Rotation)
         .add_external_type(Velocity)
         .add_external_type(Acceleration)
+        .add_external_type(MovementParams)
+        .add_external_type(Floor)
         .add_external_type(Vector)
         .add_external_type(TextSize)
         .add_external_type(Line)
@@ -199,11 +255,25 @@ pub fn create_config(rebo_stream_tx: Sender<ReboToStream>) -> ReboConfig {
         .add_external_type(RecordFrame)
         .add_external_type(InputEvent)
         .add_external_type(RefunctMap)
+        .add_external_type(MapMetadata)
+        .add_external_type(SpawnPoint)
+        .add_external_type(MapWorldSettings)
+        .add_external_type(ClusterTrigger)
         .add_external_type(Cluster)
         .add_external_type(Element)
+        .add_external_type(PipeExit)
         .add_external_type(ElementType)
         .add_external_type(ElementIndex)
         .add_external_type(Bounds)
+        .add_external_type(TraceHit)
+        .add_external_type(ActorId)
+        .add_external_type(SpawnActorError)
+        .add_external_type(DestroyActorError)
+        .add_external_type(DumpSdkError)
+        .add_external_type(MapEditError)
+        .add_external_type(ElementState)
+        .add_external_type(ElementStateError)
+        .add_external_type(ClusterError)
         .add_external_type(TimeOfDay)
         .add_external_type(Game)
         .add_external_type(Segment)
@@ -220,6 +290,22 @@ pub fn create_config(rebo_stream_tx: Sender<ReboToStream>) -> ReboConfig {
         .add_external_type(IcedText)
         .add_external_type(IcedRow)
         .add_external_type(IcedColumn)
+        .add_external_type(reflection::UeObject)
+        .add_external_type(reflection::UeValue)
+        .add_external_type(reflection::UeField)
+        .add_external_type(reflection::UeMapEntry)
+        .add_external_type(reflection::ReflectionError)
+        .add_external_type(reflection::UeFunctionCall)
+        .add_external_type(ghost::GhostDisplay)
+        .add_external_type(ghost::GhostError)
+        .add_external_type(ghost::GhostSplit)
+        .add_external_type(ghost::GhostInfo)
+        .add_external_type(camera::CameraMode)
+        .add_external_type(camera::CameraInput)
+        .add_external_type(camera::CameraError)
+        .add_external_type(trail::TrailSettings)
+        .add_external_type(trail::TrajectorySettings)
+        .add_external_type(trail::TrailError)
         .add_required_rebo_function(element_pressed)
         .add_required_rebo_function(element_released)
         .add_required_rebo_function(on_key_down)
@@ -238,6 +324,13 @@ pub fn create_config(rebo_stream_tx: Sender<ReboToStream>) -> ReboConfig {
         .add_required_rebo_function(on_level_state_change)
         .add_required_rebo_function(on_resolution_change)
         .add_required_rebo_function(on_menu_open)
+        .add_required_rebo_function(on_ue_event)
+        .add_required_rebo_function(on_cube_collected)
+        .add_required_rebo_function(on_underwater_changed)
+        .add_required_rebo_function(on_pipe_entered)
+        .add_required_rebo_function(on_pipe_exited)
+        .add_required_rebo_function(on_springpad_launched)
+        .add_required_rebo_function(on_fell_below_kill_z)
     ;
     if let Some(working_dir) = &STATE.lock().unwrap().as_ref().unwrap().working_dir {
         cfg = cfg.include_config(IncludeConfig::InDirectory(PathBuf::from(working_dir)));
@@ -289,6 +382,12 @@ fn interrupt_function<'i>(_vm: &mut VmContext<'i, '_, '_>) -> Result<(), ExecErr
                     log!("Should Exit");
                     return Err(ExecError::Panic);
                 }
+                StreamToRebo::Unload => {
+                    log!("Should Exit and unload");
+                    STATE.lock().unwrap().as_mut().unwrap().unload_requested = true;
+                    return Err(ExecError::Panic);
+                }
+                StreamToRebo::DumpSdk(path) => dump_sdk_for_tool(path),
             }
             Err(TryRecvError::Empty) => return Ok(()),
             Err(e) => {
@@ -332,11 +431,25 @@ fn step_internal<'i>(vm: &mut VmContext<'i, '_, '_>, expr_span: Span, suspend: S
         // yield back to UE stack, but not necessarily the UE loop
         let evt = YIELDER.with(|yielder| unsafe { (*yielder.get()).suspend(suspend) });
         match evt {
-            UeEvent::Tick => to_be_returned = Some(Step::Tick),
+            UeEvent::Tick => {
+                ghost::tick(FApp::delta());
+                camera::tick(FApp::delta());
+                trail::tick();
+                to_be_returned = Some(Step::Tick)
+            },
             UeEvent::ElementPressed(index) => element_pressed(vm, index)?,
             UeEvent::ElementReleased(index) => element_released(vm, index)?,
+            UeEvent::CubeCollected(index) => on_cube_collected(vm, index)?,
+            UeEvent::UnderwaterChanged(underwater) => on_underwater_changed(vm, underwater)?,
+            UeEvent::PipeEntered(index) => on_pipe_entered(vm, index)?,
+            UeEvent::PipeExited(index) => on_pipe_exited(vm, index)?,
+            UeEvent::SpringpadLaunched(index) => on_springpad_launched(vm, index)?,
+            UeEvent::FellBelowKillZ => on_fell_below_kill_z(vm)?,
             UeEvent::NothingHappened => to_be_returned = Some(Step::Yield),
-            UeEvent::NewGame => to_be_returned = Some(Step::NewGame),
+            UeEvent::NewGame => {
+                ghost::restart_all();
+                to_be_returned = Some(Step::NewGame)
+            },
             UeEvent::KeyDown(key, repeat) => {
                 let key = STATE.lock().unwrap().as_mut().unwrap().ui.key_pressed(key);
                 on_key_down(vm, key.raw_key_code, key.raw_character_code, repeat)?
@@ -376,6 +489,8 @@ fn step_internal<'i>(vm: &mut VmContext<'i, '_, '_>, expr_span: Span, suspend: S
                     AMyCharacter::set_mouse_cursor(lock.interaction.into());
                     AMyHud::draw_texture_simple(&lock.texture, 0., 0., 1., false);
                 }
+                ghost::draw_markers();
+                debug_draw::draw();
                 // draw old UI
                 draw_hud(vm)?
             },
@@ -385,6 +500,7 @@ fn step_internal<'i>(vm: &mut VmContext<'i, '_, '_>, expr_span: Span, suspend: S
                 on_resolution_change(vm)?
             },
             UeEvent::AddToScreen => on_menu_open(vm)?,
+            UeEvent::ProcessEvent(call) => on_ue_event(vm, call)?,
         }
 
         // check websocket
@@ -416,6 +532,10 @@ fn step_internal<'i>(vm: &mut VmContext<'i, '_, '_>, expr_span: Span, suspend: S
             Some(ret) => {
                 // call level-state event function
                 let new_level_state = LevelState::get();
+                if new_level_state.level > old_level_state.level {
+                    let (x, y, z) = AMyCharacter::get_player().location();
+                    ghost::split(new_level_state.level, Location { x, y, z });
+                }
                 if old_level_state != new_level_state {
                     on_level_state_change(vm, old_level_state.clone(), new_level_state)?;
                 }
@@ -430,6 +550,12 @@ fn step_internal<'i>(vm: &mut VmContext<'i, '_, '_>, expr_span: Span, suspend: S
 extern "rebo" {
     fn element_pressed(index: ElementIndex);
     fn element_released(index: ElementIndex);
+    fn on_cube_collected(index: ElementIndex);
+    fn on_underwater_changed(underwater: bool);
+    fn on_pipe_entered(index: ElementIndex);
+    fn on_pipe_exited(index: ElementIndex);
+    fn on_springpad_launched(index: ElementIndex);
+    fn on_fell_below_kill_z();
     fn on_key_down(key_code: i32, character_code: u32, is_repeat: bool);
     fn on_key_up(key_code: i32, character_code: u32, is_repeat: bool);
     fn on_mouse_move(x: i32, y: i32);
@@ -446,6 +572,7 @@ extern "rebo" {
     fn on_level_state_change(old: LevelState, new: LevelState);
     fn on_resolution_change();
     fn on_menu_open();
+    fn on_ue_event(event: UeFunctionCall);
 }
 
 fn config_path() -> PathBuf {
@@ -482,7 +609,7 @@ fn store_settings(settings: Map<String, String>) {
 }
 
 #[derive(Serialize, Deserialize)]
-struct Recording {
+pub(super) struct Recording {
     version: i32,
     author: String,
     steam_id: u64,
@@ -494,14 +621,14 @@ struct Recording {
     base_speed: f32,
     max_walk_speed: f32,
     max_bonus_speed: f32,
-    frames: Vec<RecordFrame>,
+    pub(super) frames: Vec<RecordFrame>,
 }
 #[derive(rebo::ExternalType, Serialize, Deserialize, Clone)]
-struct RecordFrame {
-    delta: f64,
+pub(super) struct RecordFrame {
+    pub(super) delta: f64,
     events: Vec<InputEvent>,
-    location: Location,
-    rotation: Rotation,
+    pub(super) location: Location,
+    pub(super) rotation: Rotation,
     velocity: Velocity,
     acceleration: Acceleration,
 }
@@ -551,11 +678,14 @@ fn save_recording(filename: String, frames: Vec<RecordFrame>, recording_start_ti
 }
 #[rebo::function("Tas::load_recording")]
 fn load_recording(filename: String) -> Vec<RecordFrame> {
+    read_recording(&filename).unwrap().frames
+}
+/// Read a recording from the recordings folder, `None` if it doesn't exist or can't be parsed
+pub(super) fn read_recording(filename: &str) -> Option<Recording> {
     let filename = sanitize_filename::sanitize(filename);
     let path = recording_path().join(filename);
-    let content = std::fs::read_to_string(path).unwrap();
-    let res: Recording = serde_json::from_str(&content).unwrap();
-    res.frames
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
 }
 #[rebo::function("Tas::remove_recording")]
 fn remove_recording(filename: String) -> bool {
@@ -601,10 +731,10 @@ fn set_delta(delta: Option<f64>) {
     STATE.lock().unwrap().as_mut().unwrap().delta = delta;
 }
 #[derive(Debug, Clone, Copy, rebo::ExternalType, Serialize, Deserialize)]
-struct Location {
-    x: f32,
-    y: f32,
-    z: f32,
+pub(super) struct Location {
+    pub(super) x: f32,
+    pub(super) y: f32,
+    pub(super) z: f32,
 }
 #[rebo::function("Tas::get_location")]
 fn get_location() -> Location {
@@ -616,10 +746,10 @@ fn set_location(loc: Location) {
     AMyCharacter::get_player().set_location(loc.x, loc.y, loc.z);
 }
 #[derive(Debug, Clone, Copy, rebo::ExternalType, Serialize, Deserialize)]
-struct Rotation {
-    pitch: f32,
-    yaw: f32,
-    roll: f32,
+pub(super) struct Rotation {
+    pub(super) pitch: f32,
+    pub(super) yaw: f32,
+    pub(super) roll: f32,
 }
 #[rebo::function("Tas::get_rotation")]
 fn get_rotation() -> Rotation {
@@ -684,6 +814,57 @@ fn get_max_fly_speed() -> f32 {
 fn set_max_fly_speed(speed: f32) {
     AMyCharacter::get_player().set_max_fly_speed(speed);
 }
+#[derive(Debug, Clone, Copy, rebo::ExternalType)]
+struct MovementParams {
+    jump_z_velocity: f32,
+    air_control: f32,
+    gravity_scale: f32,
+    ground_friction: f32,
+    /// ignored by `Tas::set_movement_params`, use `Tas::set_movement_mode` instead
+    is_falling: bool,
+    is_crouched: bool,
+}
+fn movement_params(character: &AMyCharacter) -> MovementParams {
+    MovementParams {
+        jump_z_velocity: character.movement_property("JumpZVelocity"),
+        air_control: character.movement_property("AirControl"),
+        gravity_scale: character.movement_property("GravityScale"),
+        ground_friction: character.movement_property("GroundFriction"),
+        is_falling: character.is_falling(),
+        is_crouched: character.is_crouched(),
+    }
+}
+fn apply_movement_params(character: &AMyCharacter, params: MovementParams) {
+    character.set_movement_property("JumpZVelocity", params.jump_z_velocity);
+    character.set_movement_property("AirControl", params.air_control);
+    character.set_movement_property("GravityScale", params.gravity_scale);
+    character.set_movement_property("GroundFriction", params.ground_friction);
+    if character.is_crouched() != params.is_crouched {
+        character.set_crouched(params.is_crouched);
+    }
+}
+#[rebo::function("Tas::get_movement_params")]
+fn get_movement_params() -> MovementParams {
+    movement_params(&AMyCharacter::get_player())
+}
+#[rebo::function("Tas::set_movement_params")]
+fn set_movement_params(params: MovementParams) {
+    apply_movement_params(&AMyCharacter::get_player(), params);
+}
+#[derive(Debug, Clone, rebo::ExternalType)]
+struct Floor {
+    /// whether the floor isn't too steep to walk on
+    walkable: bool,
+    distance: f32,
+    /// `None` if there is no floor below the player or its hit can't be read (logged)
+    hit: Option<TraceHit>,
+}
+/// The floor found below the player during the last movement update
+#[rebo::function("Tas::get_floor")]
+fn get_floor() -> Floor {
+    let (walkable, distance, hit) = AMyCharacter::get_player().current_floor();
+    Floor { walkable, distance, hit: hit.map(TraceHit::from) }
+}
 #[rebo::function("Tas::get_max_walk_speed")]
 fn get_max_walk_speed() -> f32 {
     AMyCharacter::get_max_walk_speed()
@@ -820,10 +1001,10 @@ fn player_minimap_size() -> Size {
     }
 }
 #[derive(Debug, Clone, Copy, rebo::ExternalType)]
-struct Vector {
-    x: f32,
-    y: f32,
-    z: f32,
+pub(super) struct Vector {
+    pub(super) x: f32,
+    pub(super) y: f32,
+    pub(super) z: f32,
 }
 #[rebo::function("Tas::project")]
 fn project(vec: Vector) -> Vector {
@@ -872,6 +1053,20 @@ fn set_pawn_velocity(pawn_id: u32, vel: Velocity) {
     let my_character = state.pawns.get_mut(&pawn_id).expect("pawn_id not valid");
     my_character.set_velocity(vel.x, vel.y, vel.z);
 }
+#[rebo::function("Tas::get_pawn_movement_params")]
+fn get_pawn_movement_params(pawn_id: u32) -> MovementParams {
+    let state = STATE.lock().unwrap();
+    let state = state.as_ref().unwrap();
+    let my_character = state.pawns.get(&pawn_id).expect("pawn_id not valid");
+    movement_params(my_character)
+}
+#[rebo::function("Tas::set_pawn_movement_params")]
+fn set_pawn_movement_params(pawn_id: u32, params: MovementParams) {
+    let state = STATE.lock().unwrap();
+    let state = state.as_ref().unwrap();
+    let my_character = state.pawns.get(&pawn_id).expect("pawn_id not valid");
+    apply_movement_params(my_character, params);
+}
 #[rebo::function("Tas::pawn_location")]
 fn pawn_location(pawn_id: u32) -> Location {
     let mut state = STATE.lock().unwrap();
@@ -880,6 +1075,67 @@ fn pawn_location(pawn_id: u32) -> Location {
     let (x, y, z) = my_character.location();
     Location { x, y, z }
 }
+#[derive(Debug, Clone, Copy, rebo::ExternalType)]
+struct ActorId {
+    id: u32,
+}
+#[derive(Debug, Clone, rebo::ExternalType)]
+enum SpawnActorError {
+    /// class name
+    ClassNotFound(String),
+    /// class name
+    NotAnActorClass(String),
+    SpawnFailed,
+}
+/// Spawn an actor of any loaded class, e.g. `BP_IslandChunk_C`.
+/// The actor is destroyed automatically once the rebo script ends.
+#[rebo::function("Tas::spawn_actor")]
+fn spawn_actor(class_name: String, loc: Location, rot: Rotation, scale: Vector) -> Result<ActorId, SpawnActorError> {
+    let index = UeScope::with(|scope| {
+        let class = scope.iter_global_object_array()
+            .map(|item| item.object())
+            .find(|object| object.name() == class_name && object.class().extends_from("Class"))
+            .ok_or_else(|| SpawnActorError::ClassNotFound(class_name.clone()))?
+            .upcast::<ClassWrapper>();
+        if !class.extends_from("Actor") {
+            return Err(SpawnActorError::NotAnActorClass(class_name.clone()));
+        }
+        let location = FVector { x: loc.x, y: loc.y, z: loc.z };
+        let rotation = FRotator { pitch: rot.pitch, yaw: rot.yaw, roll: rot.roll };
+        let actor = UWorld::spawn_actor_of_class(&class, location, rotation);
+        if actor.is_null() {
+            return Err(SpawnActorError::SpawnFailed);
+        }
+        let actor = unsafe { ActorWrapper::new(actor) };
+        if actor.get_field("RootComponent").unwrap_object().is_some() {
+            USceneComponent::set_world_scale(FVector { x: scale.x, y: scale.y, z: scale.z }, &actor);
+        }
+        Ok(scope.object_index(&actor))
+    })?;
+    let mut state = STATE.lock().unwrap();
+    let state = state.as_mut().unwrap();
+    let id = state.actor_id;
+    state.actor_id += 1;
+    state.actors.insert(id, index);
+    Ok(ActorId { id })
+}
+#[derive(Debug, Clone, Copy, rebo::ExternalType)]
+enum DestroyActorError {
+    /// the actor wasn't spawned by `Tas::spawn_actor` or was already destroyed
+    UnknownActorId,
+}
+#[rebo::function("Tas::destroy_actor")]
+fn destroy_actor(actor: ActorId) -> Result<(), DestroyActorError> {
+    let index = STATE.lock().unwrap().as_mut().unwrap().actors.remove(&actor.id)
+        .ok_or(DestroyActorError::UnknownActorId)?;
+    UeScope::with(|scope| {
+        // the actor may have already been destroyed by the game
+        if let Ok(actor) = scope.try_get(index) {
+            UWorld::destroy_spawned_actor(&actor);
+        }
+    });
+    Ok(())
+}
 #[derive(rebo::ExternalType)]
 enum Server {
     Localhost,
@@ -1029,47 +1285,201 @@ fn get_level() -> i32 {
 fn set_level(level: i32) {
     LevelState::set_level(level);
 }
+/// Press the platform, button or lift with the game's own logic, as if the player landed on it and left it again
+fn press_element(actor: &ActorWrapper<'_>) {
+    let state = STATE.lock().unwrap();
+    let state = state.as_ref().unwrap();
+    unsafe {
+        let liftbase = actor.as_ptr() as *mut ALiftBaseUE;
+        let character = AMyCharacter::get_player().as_ptr();
+        state.hooks.aliftbase.add_based_character(liftbase, character);
+        state.hooks.aliftbase.remove_based_character(liftbase, character);
+    }
+}
+/// Collect the cube with the game's own logic by calling its overlap event with the player
+fn collect_cube(actor: &ActorWrapper<'_>) {
+    let trigger_fn = actor
+        .class()
+        .find_function("BndEvt__Trigger_K2Node_ComponentBoundEvent_24_ComponentBeginOverlapSignature__DelegateSignature")
+        .unwrap();
+    let params = trigger_fn.create_argument_struct();
+    let movement = unsafe { ObjectWrapper::new(AMyCharacter::get_player().movement() as *mut UObject) };
+    let updated_primitive = movement.get_field("UpdatedPrimitive").unwrap::<ObjectWrapper>();
+    let trigger = actor.get_field("Trigger").unwrap::<ObjectWrapper>();
+    params.get_field("OverlappedComponent").set_object(&updated_primitive);
+    params.get_field("OtherComp").set_object(&trigger);
+    params.get_field("OtherActor").set_object(&actor);
+    params.get_field("OtherBodyIndex").unwrap::<&Cell<i32>>().set(0);
+    unsafe {
+        trigger_fn.call(actor.as_ptr(), &params);
+    }
+}
 #[rebo::function("Tas::trigger_element")]
 fn trigger_element(index: ElementIndex) {
-    fn add_remove_based_character(actor: &ActorWrapper<'_>) {
-        let state = STATE.lock().unwrap();
-        let state = state.as_ref().unwrap();
-        unsafe {
-            let liftbase = actor.as_ptr() as *mut ALiftBaseUE;
-            let character = AMyCharacter::get_player().as_ptr();
-            state.hooks.aliftbase.add_based_character(liftbase, character);
-            state.hooks.aliftbase.remove_based_character(liftbase, character);
-        }
-    }
-    fn collect_cube(actor: &ActorWrapper<'_>) {
-        let trigger_fn = actor
-            .class()
-            .find_function("BndEvt__Trigger_K2Node_ComponentBoundEvent_24_ComponentBeginOverlapSignature__DelegateSignature")
-            .unwrap();
-        let params = trigger_fn.create_argument_struct();
+    /// Call the actor's first begin-overlap event with the player, e.g. to enter a pipe
+    fn begin_overlap(actor: &ActorWrapper<'_>) {
+        let Some(event) = actor.class().iter_functions().find(|fun| {
+            let name = fun.name();
+            name.starts_with("BndEvt__") && name.contains("ComponentBeginOverlapSignature")
+        }) else {
+            log!("{} doesn't have a begin-overlap event", actor.name());
+            return;
+        };
+        // the event's name starts with the bound component like `BndEvt__Trigger_K2Node_...`
+        let name = event.name();
+        let component_name = &name["BndEvt__".len()..name.find("_K2Node").unwrap_or(name.len())];
+        let Some(component) = actor.try_get_field(component_name).and_then(|field| field.unwrap_object()) else {
+            log!("{} doesn't have the component {component_name:?}", actor.name());
+            return;
+        };
+        let player = unsafe { ObjectWrapper::new(AMyCharacter::get_player().as_ptr() as *mut UObject) };
         let movement = unsafe { ObjectWrapper::new(AMyCharacter::get_player().movement() as *mut UObject) };
-        let updated_primitive = movement.get_field("UpdatedPrimitive").unwrap::<ObjectWrapper>();
-        let trigger = actor.get_field("Trigger").unwrap::<ObjectWrapper>();
-        params.get_field("OverlappedComponent").set_object(&updated_primitive);
-        params.get_field("OtherComp").set_object(&trigger);
-        params.get_field("OtherActor").set_object(&actor);
+        let capsule = movement.get_field("UpdatedPrimitive").unwrap::<ObjectWrapper>();
+        let params = event.create_argument_struct();
+        params.get_field("OverlappedComponent").set_object(&component);
+        params.get_field("OtherActor").set_object(&player);
+        params.get_field("OtherComp").set_object(&capsule);
         params.get_field("OtherBodyIndex").unwrap::<&Cell<i32>>().set(0);
         unsafe {
-            trigger_fn.call(actor.as_ptr(), &params);
+            event.call(actor.as_ptr(), &params);
         }
     }
     UeScope::with(|scope| {
         let levels = LEVELS.lock().unwrap();
         match index.element_type {
-            ElementType::Platform => add_remove_based_character(&*scope.get(levels[index.cluster_index].platforms[index.element_index])),
+            ElementType::Platform => press_element(&*scope.get(levels[index.cluster_index].platforms[index.element_index])),
             ElementType::Cube => collect_cube(&*scope.get(levels[index.cluster_index].cubes[index.element_index])),
-            ElementType::Button => add_remove_based_character(&*scope.get(levels[index.cluster_index].buttons[index.element_index])),
-            ElementType::Lift => add_remove_based_character(&*scope.get(levels[index.cluster_index].lifts[index.element_index])),
-            ElementType::Pipe => (),
-            ElementType::Springpad => (),
+            ElementType::Button => press_element(&*scope.get(levels[index.cluster_index].buttons[index.element_index])),
+            ElementType::Lift => press_element(&*scope.get(levels[index.cluster_index].lifts[index.element_index])),
+            ElementType::Pipe => begin_overlap(&*scope.get(levels[index.cluster_index].pipes[index.element_index])),
+            ElementType::Springpad => begin_overlap(&*scope.get(levels[index.cluster_index].springpads[index.element_index])),
         }
+    });
+    // we call the original functions, so the element-pressed hook doesn't see this press
+    fire_cluster_triggers(index);
+}
+/// Runtime flags of an element, `None` if the element doesn't have the flag
+#[derive(Debug, Clone, rebo::ExternalType)]
+struct ElementState {
+    /// platforms and buttons
+    pressed: Option<bool>,
+    /// cubes
+    collected: Option<bool>,
+    /// whether the element's cluster has risen
+    risen: Option<bool>,
+}
+#[derive(Debug, Clone, rebo::ExternalType)]
+enum ElementStateError {
+    InvalidElementIndex,
+    /// flag name, if the element doesn't have the flag, e.g. `collected` of a platform
+    UnknownFlag(String),
+    /// flag name, if the game can't undo it, i.e. unpressing an element or uncollecting a cube
+    Irreversible(String),
+    /// the height the element's cluster rises to isn't known, see `Tas::cluster_raise`
+    UnknownTargetLocation,
+}
+fn check_element_index(levels: &[Level], index: ElementIndex) -> Result<(), ElementStateError> {
+    let level = levels.get(index.cluster_index).ok_or(ElementStateError::InvalidElementIndex)?;
+    let count = match index.element_type {
+        ElementType::Platform => level.platforms.len(),
+        ElementType::Cube => level.cubes.len(),
+        ElementType::Button => level.buttons.len(),
+        ElementType::Lift => level.lifts.len(),
+        ElementType::Pipe => level.pipes.len(),
+        ElementType::Springpad => level.springpads.len(),
+    };
+    if index.element_index >= count {
+        return Err(ElementStateError::InvalidElementIndex);
+    }
+    Ok(())
+}
+fn element_state(scope: &UeScope, levels: &[Level], index: ElementIndex) -> ElementState {
+    let level = &levels[index.cluster_index];
+    let pressed = match index.element_type {
+        ElementType::Platform => scope.get(level.platforms[index.element_index]).pressed(),
+        ElementType::Button => scope.get(level.buttons[index.element_index]).pressed(),
+        _ => None,
+    };
+    let collected = match index.element_type {
+        ElementType::Cube => scope.get(level.cubes[index.element_index]).collected(),
+        _ => None,
+    };
+    let risen = scope.get(level.level).risen();
+    ElementState { pressed, collected, risen }
+}
+#[rebo::function("Tas::get_element_state")]
+fn get_element_state(index: ElementIndex) -> Result<ElementState, ElementStateError> {
+    UeScope::with(|scope| {
+        let levels = LEVELS.lock().unwrap();
+        check_element_index(&levels, index)?;
+        Ok(element_state(scope, &levels, index))
     })
 }
+/// Bring an element into the given state with the game's own logic, keeping flags which are `None`.
+///
+/// Pressing runs the game's press of the platform or button like `Tas::trigger_element`, including
+/// its look, the `LevelState` counts and rising the next cluster for buttons.
+/// Collecting runs the cube's collect event. `risen` raises or lowers the cluster like
+/// `Tas::cluster_raise` and `Tas::cluster_lower`.
+/// The game can't unpress elements or uncollect cubes, so that's rejected with `Irreversible`,
+/// a new game resets them.
+/// Nothing is changed if any of the flags can't be set.
+#[rebo::function("Tas::set_element_state")]
+fn set_element_state(index: ElementIndex, state: ElementState) -> Result<(), ElementStateError> {
+    let pressed = UeScope::with(|scope| {
+        let levels = LEVELS.lock().unwrap();
+        check_element_index(&levels, index)?;
+        let current = element_state(scope, &levels, index);
+        let unknown = |flag: &str| ElementStateError::UnknownFlag(flag.to_string());
+        let irreversible = |flag: &str| ElementStateError::Irreversible(flag.to_string());
+
+        // validate all flags before changing anything
+        let press = match (state.pressed, current.pressed) {
+            (None, _) => false,
+            (Some(_), None) => return Err(unknown("pressed")),
+            (Some(pressed), Some(current)) if pressed == current => false,
+            (Some(true), Some(false)) => true,
+            (Some(false), Some(true)) => return Err(irreversible("pressed")),
+        };
+        let collect = match (state.collected, current.collected) {
+            (None, _) => false,
+            (Some(_), None) => return Err(unknown("collected")),
+            (Some(collected), Some(current)) if collected == current => false,
+            (Some(true), Some(false)) => true,
+            (Some(false), Some(true)) => return Err(irreversible("collected")),
+        };
+        let level = scope.get(levels[index.cluster_index].level);
+        let rise = match (state.risen, current.risen) {
+            (None, _) => None,
+            (Some(_), None) => return Err(unknown("risen")),
+            (Some(risen), Some(current)) if risen == current => None,
+            (Some(risen), Some(_)) => {
+                let (_, _, source_z) = level.source_location();
+                let (_, _, target_z) = level.target_location().ok_or(ElementStateError::UnknownTargetLocation)?;
+                Some((risen, source_z, target_z))
+            }
+        };
+
+        let actor = get_indexed_actor(scope, &levels, index);
+        if press {
+            press_element(&actor);
+        }
+        if collect {
+            collect_cube(&actor);
+        }
+        match rise {
+            Some((true, _, target_z)) => raise_level(&level, target_z),
+            Some((false, source_z, _)) => lower_level(&level, source_z),
+            None => (),
+        }
+        Ok(press)
+    })?;
+    if pressed {
+        // we call the original functions, so the element-pressed hook doesn't see this press
+        fire_cluster_triggers(index);
+    }
+    Ok(())
+}
 #[rebo::function("Tas::set_start_seconds")]
 fn set_start_seconds(start_seconds: i32) {
     LevelState::set_start_seconds(start_seconds);
@@ -1115,6 +1525,7 @@ fn show_hud() {
 fn set_all_cluster_speeds(speed: f32) {
     // initialize before we change anything
     let _ = &*ORIGINAL_MAP;
+    FROZEN_CLUSTERS.lock().unwrap().clear();
     UeScope::with(|scope| {
         for level in &*LEVELS.lock().unwrap() {
             scope.get(level.level).set_speed(speed);
@@ -1122,6 +1533,88 @@ fn set_all_cluster_speeds(speed: f32) {
     })
 }
 
+/// rise speeds of frozen clusters before they were frozen
+static FROZEN_CLUSTERS: Lazy<Mutex<HashMap<usize, f32>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+#[derive(Debug, Clone, rebo::ExternalType)]
+enum ClusterError {
+    InvalidClusterIndex,
+    /// the location the cluster rises to can't be found on its level root
+    UnknownTargetLocation,
+}
+/// Run `f` with the cluster's level root, its `SourcePosition` z and the z it rises to
+fn with_cluster<T>(cluster_index: usize, f: impl FnOnce(&LevelWrapper, f32, f32) -> T) -> Result<T, ClusterError> {
+    UeScope::with(|scope| {
+        let levels = LEVELS.lock().unwrap();
+        let level = levels.get(cluster_index).ok_or(ClusterError::InvalidClusterIndex)?;
+        let level = scope.get(level.level);
+        let (_, _, source_z) = level.source_location();
+        let (_, _, target_z) = level.target_location().ok_or(ClusterError::UnknownTargetLocation)?;
+        Ok(f(&level, source_z, target_z))
+    })
+}
+fn move_level_root(level: &LevelWrapper, z: f32) {
+    let (x, y, _) = level.relative_location();
+    let (pitch, yaw, roll) = level.relative_rotation();
+    USceneComponent::set_world_location_and_rotation(FVector { x, y, z }, FRotator { pitch, yaw, roll }, level);
+}
+fn raise_level(level: &LevelWrapper, target_z: f32) {
+    move_level_root(level, target_z);
+    // mark its level as reached, the level root compares it every tick (see `UNREACHABLE_LEVEL_INDEX`)
+    level.set_level_index(LevelState::get_level());
+    level.set_risen(true);
+}
+fn lower_level(level: &LevelWrapper, source_z: f32) {
+    move_level_root(level, source_z);
+    level.set_level_index(UNREACHABLE_LEVEL_INDEX);
+    level.set_risen(false);
+}
+/// Instantly move the cluster to the height it rises to.
+#[rebo::function("Tas::cluster_raise")]
+fn cluster_raise(cluster_index: usize) -> Result<(), ClusterError> {
+    with_cluster(cluster_index, |level, _, target_z| raise_level(level, target_z))
+}
+/// Instantly move the cluster back to its `SourcePosition`.
+///
+/// The cluster doesn't rise again until it's raised or a new game is started.
+#[rebo::function("Tas::cluster_lower")]
+fn cluster_lower(cluster_index: usize) -> Result<(), ClusterError> {
+    with_cluster(cluster_index, |level, source_z, _| lower_level(level, source_z))
+}
+/// Pause or resume the cluster's rise by setting its rise speed to 0 and back.
+///
+/// Changing the rise speeds, e.g. by applying a map, resumes all clusters.
+#[rebo::function("Tas::cluster_set_frozen")]
+fn cluster_set_frozen(cluster_index: usize, frozen: bool) -> Result<(), ClusterError> {
+    UeScope::with(|scope| {
+        let levels = LEVELS.lock().unwrap();
+        let level = scope.get(levels.get(cluster_index).ok_or(ClusterError::InvalidClusterIndex)?.level);
+        let mut frozen_clusters = FROZEN_CLUSTERS.lock().unwrap();
+        match (frozen, frozen_clusters.get(&cluster_index)) {
+            (true, None) => {
+                frozen_clusters.insert(cluster_index, level.speed());
+                level.set_speed(0.);
+            }
+            (false, Some(&speed)) => {
+                level.set_speed(speed);
+                frozen_clusters.remove(&cluster_index);
+            }
+            _ => (),
+        }
+        Ok(())
+    })
+}
+/// How far the cluster has risen from its `SourcePosition`, from 0 (lowered) to 1 (fully risen)
+#[rebo::function("Tas::cluster_rise_progress")]
+fn cluster_rise_progress(cluster_index: usize) -> Result<f32, ClusterError> {
+    with_cluster(cluster_index, |level, source_z, target_z| {
+        let (_, _, z) = level.relative_location();
+        if target_z == source_z {
+            return 1.;
+        }
+        ((z - source_z) / (target_z - source_z)).clamp(0., 1.)
+    })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct RefunctMapV0 {
     clusters: Vec<ClusterV0>,
@@ -1151,7 +1644,7 @@ struct ElementV0 {
 // - Cluster 24 springpad -> Cluster 25 springpad
 // - Cluster 26 springpad -> Cluster 25 springpad
 
-fn migrate_v0_to_v1(map: RefunctMapV0) -> RefunctMap {
+fn migrate_v0_to_v1(map: RefunctMapV0) -> RefunctMapV1 {
     fn migrate_element(orig: &RefunctMap, e: ElementV0, index: ElementIndex) -> Element {
         let orig = get_indexed_element(orig, index);
         Element {
@@ -1164,10 +1657,15 @@ fn migrate_v0_to_v1(map: RefunctMapV0) -> RefunctMap {
             sizex: e.xscale * orig.sizex,
             sizey: e.yscale * orig.sizey,
             sizez: e.zscale * orig.sizez,
+            hidden: false,
+            launch_velocity: None,
+            pipe_exit: None,
+            lift_distance: None,
+            lift_speed: None,
         }
     }
     let orig = &*ORIGINAL_MAP;
-    RefunctMap {
+    RefunctMapV1 {
         version: 1,
         clusters: map.clusters.into_iter().enumerate().map(|(cluster_index, cluster)| Cluster {
             z: orig.clusters[cluster_index].z,
@@ -1182,12 +1680,64 @@ fn migrate_v0_to_v1(map: RefunctMapV0) -> RefunctMap {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RefunctMapV1 {
+    version: u32,
+    clusters: Vec<Cluster>,
+}
+
+fn migrate_v1_to_v2(map: RefunctMapV1) -> RefunctMap {
+    RefunctMap {
+        version: 2,
+        metadata: MapMetadata { name: String::new(), author: String::new(), description: String::new() },
+        spawn: None,
+        world: MapWorldSettings { gravity: None, kill_z: None, time_of_day: None },
+        triggers: Vec::new(),
+        clusters: map.clusters,
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, rebo::ExternalType)]
 pub struct RefunctMap {
     version: u32,
+    metadata: MapMetadata,
+    /// where the player starts when starting a new game, `None` to keep the current one
+    spawn: Option<SpawnPoint>,
+    world: MapWorldSettings,
+    /// buttons raising clusters instead of the game's order, empty to keep the game's order
+    #[serde(default)]
+    triggers: Vec<ClusterTrigger>,
     clusters: Vec<Cluster>,
 }
 #[derive(Debug, Clone, Serialize, Deserialize, rebo::ExternalType)]
+struct MapMetadata {
+    name: String,
+    author: String,
+    description: String,
+}
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, rebo::ExternalType)]
+struct SpawnPoint {
+    location: Location,
+    rotation: Rotation,
+}
+/// World settings the map relies on, `None` keeps the current value
+#[derive(Debug, Clone, Serialize, Deserialize, rebo::ExternalType)]
+struct MapWorldSettings {
+    gravity: Option<f32>,
+    kill_z: Option<f32>,
+    /// in minutes, see `Tas::set_time_of_day`
+    time_of_day: Option<f32>,
+}
+/// Pressing the button raises the given clusters.
+///
+/// Clusters which are raised by any trigger don't rise in the game's order anymore.
+#[derive(Debug, Clone, Serialize, Deserialize, rebo::ExternalType)]
+struct ClusterTrigger {
+    button_cluster_index: usize,
+    button_index: usize,
+    clusters: Vec<usize>,
+}
+#[derive(Debug, Clone, Serialize, Deserialize, rebo::ExternalType)]
 struct Cluster {
     z: f32,
     rise_speed: f32,
@@ -1198,7 +1748,7 @@ struct Cluster {
     pipes: Vec<Element>,
     springpads: Vec<Element>,
 }
-#[derive(Debug, Clone, Serialize, Deserialize, rebo::ExternalType)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, rebo::ExternalType)]
 struct Element {
     x: f32,
     y: f32,
@@ -1209,6 +1759,49 @@ struct Element {
     sizex: f32,
     sizey: f32,
     sizez: f32,
+    /// hidden elements are invisible and don't have collision
+    #[serde(default)]
+    hidden: bool,
+    /// springpads only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    launch_velocity: Option<f32>,
+    /// pipes only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pipe_exit: Option<PipeExit>,
+    /// lifts only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lift_distance: Option<f32>,
+    /// lifts only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lift_speed: Option<f32>,
+}
+/// Pipe the player exits from after entering a pipe
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, rebo::ExternalType)]
+struct PipeExit {
+    cluster_index: usize,
+    pipe_index: usize,
+}
+impl Cluster {
+    fn elements(&self, element_type: ElementType) -> &[Element] {
+        match element_type {
+            ElementType::Platform => &self.platforms,
+            ElementType::Cube => &self.cubes,
+            ElementType::Button => &self.buttons,
+            ElementType::Lift => &self.lifts,
+            ElementType::Pipe => &self.pipes,
+            ElementType::Springpad => &self.springpads,
+        }
+    }
+    fn elements_mut(&mut self, element_type: ElementType) -> &mut Vec<Element> {
+        match element_type {
+            ElementType::Platform => &mut self.platforms,
+            ElementType::Cube => &mut self.cubes,
+            ElementType::Button => &mut self.buttons,
+            ElementType::Lift => &mut self.lifts,
+            ElementType::Pipe => &mut self.pipes,
+            ElementType::Springpad => &mut self.springpads,
+        }
+    }
 }
 
 fn map_path() -> PathBuf {
@@ -1242,9 +1835,10 @@ fn load_map(filename: String) -> RefunctMap {
     let map = match version.version {
         0 => {
             let map = serde_json::from_str(&content).unwrap();
-            migrate_v0_to_v1(map)
+            migrate_v1_to_v2(migrate_v0_to_v1(map))
         }
-        1 => serde_json::from_str(&content).unwrap(),
+        1 => migrate_v1_to_v2(serde_json::from_str(&content).unwrap()),
+        2 => serde_json::from_str(&content).unwrap(),
         version => panic!("the map lives in the future (unknown map version {version})"),
     };
     map
@@ -1265,7 +1859,7 @@ fn remove_map(filename: String) -> bool {
 
 fn get_current_map(original: bool) -> RefunctMap {
     UeScope::with(|scope| {
-        fn actor_list_to_element_list<'a, T, F>(scope: &'a UeScope, level: &LevelWrapper<'a>, list: &[ObjectIndex<T>], element_type: ElementType, get_orig_size: F) -> Vec<Element>
+        fn actor_list_to_element_list<'a, T, F>(scope: &'a UeScope, cluster_index: usize, level: &LevelWrapper<'a>, list: &[ObjectIndex<T>], element_type: ElementType, get_orig_size: F) -> Vec<Element>
         where
             T: UeObjectWrapperType,
             T::UeObjectWrapper<'a>: Deref<Target = ActorWrapper<'a>>,
@@ -1274,13 +1868,17 @@ fn get_current_map(original: bool) -> RefunctMap {
             list.iter().enumerate().map(|(element_index, actor)| {
                 let actor = scope.get(actor);
                 let actor = actor.deref();
-                let index = ElementIndex { cluster_index: level.level_index(), element_type, element_index };
+                let index = ElementIndex { cluster_index, element_type, element_index };
                 let (sizex, sizey, sizez) = get_orig_size(actor, index);
                 let (_, _, lz) = level.relative_location();
                 let (ax, ay, az) = actor.absolute_location();
                 let (pitch, yaw, roll) = actor.relative_rotation();
                 let (xscale, yscale, zscale) = actor.relative_scale();
-                Element { x: ax, y: ay, z: az - lz, pitch, yaw, roll, sizex: sizex / xscale, sizey: sizey / yscale, sizez: sizez / zscale }
+                let hidden = actor.is_hidden_in_game();
+                Element {
+                    x: ax, y: ay, z: az - lz, pitch, yaw, roll, sizex: sizex / xscale, sizey: sizey / yscale, sizez: sizez / zscale, hidden,
+                    launch_velocity: None, pipe_exit: None, lift_distance: None, lift_speed: None,
+                }
             }).collect()
         }
         let get_orig_size: Box<for<'a> fn(&'a ActorWrapper, _) -> _> = if original {
@@ -1306,26 +1904,42 @@ fn get_current_map(original: bool) -> RefunctMap {
             })
         } else {
             Box::new(move |_actor: &ActorWrapper, index: ElementIndex| {
-                let e = get_indexed_element(&*ORIGINAL_MAP, index);
+                let e = get_indexed_element(&*ORIGINAL_MAP, template_index(index));
                 (e.sizex, e.sizey, e.sizez)
             })
         };
         let levels = LEVELS.lock().unwrap();
-        let clusters: Vec<Cluster> = levels.iter()
-            .map(|level| {
+        let mut clusters: Vec<Cluster> = levels.iter().enumerate()
+            .map(|(cluster_index, level)| {
                 let level_wrapper = scope.get(level.level);
                 Cluster {
                     z: level_wrapper.source_location().2,
                     rise_speed: level_wrapper.speed(),
-                    platforms: actor_list_to_element_list(scope, &level_wrapper, &level.platforms, ElementType::Platform, &get_orig_size),
-                    cubes: actor_list_to_element_list(scope, &level_wrapper, &level.cubes, ElementType::Cube, &get_orig_size),
-                    buttons: actor_list_to_element_list(scope, &level_wrapper, &level.buttons, ElementType::Button, &get_orig_size),
-                    lifts: actor_list_to_element_list(scope, &level_wrapper, &level.lifts, ElementType::Lift, &get_orig_size),
-                    pipes: actor_list_to_element_list(scope, &level_wrapper, &level.pipes, ElementType::Pipe, &get_orig_size),
-                    springpads: actor_list_to_element_list(scope, &level_wrapper, &level.springpads, ElementType::Springpad, &get_orig_size),
+                    platforms: actor_list_to_element_list(scope, cluster_index, &level_wrapper, &level.platforms, ElementType::Platform, &get_orig_size),
+                    cubes: actor_list_to_element_list(scope, cluster_index, &level_wrapper, &level.cubes, ElementType::Cube, &get_orig_size),
+                    buttons: actor_list_to_element_list(scope, cluster_index, &level_wrapper, &level.buttons, ElementType::Button, &get_orig_size),
+                    lifts: actor_list_to_element_list(scope, cluster_index, &level_wrapper, &level.lifts, ElementType::Lift, &get_orig_size),
+                    pipes: actor_list_to_element_list(scope, cluster_index, &level_wrapper, &level.pipes, ElementType::Pipe, &get_orig_size),
+                    springpads: actor_list_to_element_list(scope, cluster_index, &level_wrapper, &level.springpads, ElementType::Springpad, &get_orig_size),
                 }
             }).collect();
-        RefunctMap { version: 1, clusters }
+        read_element_params(scope, &levels, &mut clusters);
+        let spawn = UWorld::get_player_start().map(|(location, rotation)| SpawnPoint {
+            location: Location { x: location.x, y: location.y, z: location.z },
+            rotation: Rotation { pitch: rotation.pitch, yaw: rotation.yaw, roll: rotation.roll },
+        });
+        RefunctMap {
+            version: 2,
+            metadata: MapMetadata { name: String::new(), author: String::new(), description: String::new() },
+            spawn,
+            // the time of day keeps progressing, so we don't want to reset it
+            world: MapWorldSettings { gravity: Some(UWorld::get_gravity()), kill_z: Some(UWorld::get_kill_z()), time_of_day: None },
+            triggers: match original {
+                true => Vec::new(),
+                false => CLUSTER_TRIGGERS.lock().unwrap().triggers.clone(),
+            },
+            clusters,
+        }
     })
 }
 
@@ -1340,27 +1954,56 @@ pub static ORIGINAL_MAP: Lazy<RefunctMap> = Lazy::new(|| get_current_map(true));
 fn original_map() -> RefunctMap {
     ORIGINAL_MAP.clone()
 }
+/// Apply the map and reset the edit history
 pub fn apply_map_internal(map: &RefunctMap) {
+    apply_map_without_history(map);
+    *MAP_JOURNAL.lock().unwrap() = MapJournal { applied: Some(map.clone()), edit_base: None, undo: Vec::new(), redo: Vec::new() };
+}
+fn apply_map_without_history(map: &RefunctMap) {
     // initialize before we change anything
     let _ = &*ORIGINAL_MAP;
+    FROZEN_CLUSTERS.lock().unwrap().clear();
 
-    fn set_element(scope: &UeScope, levels: &[Level], target_map: &RefunctMap, index: ElementIndex) {
-        let level = scope.get(levels[index.cluster_index].level);
-        let actor = get_indexed_actor(scope, levels, index);
-        let target = get_indexed_element(target_map, index);
-        let orig = get_indexed_element(&*ORIGINAL_MAP, index);
-        let (_, _, rz) = level.relative_location();
-        let (rpitch, ryaw, rroll) = level.relative_rotation();
-        let target_location = FVector { x: target.x, y: target.y, z: target.z + rz };
-        let target_rotation = FRotator { pitch: target.pitch + rpitch, yaw: target.yaw + ryaw, roll: target.roll + rroll };
-        let target_scale = FVector { x: target.sizex / orig.sizex, y: target.sizey / orig.sizey, z: target.sizez / orig.sizez };
-        USceneComponent::set_world_location_and_rotation(target_location, target_rotation, &actor);
-        USceneComponent::set_world_scale(target_scale, &actor);
+    if let Some(spawn) = &map.spawn {
+        let SpawnPoint { location: Location { x, y, z }, rotation: Rotation { pitch, yaw, roll } } = *spawn;
+        UWorld::set_player_start(FVector { x, y, z }, FRotator { pitch, yaw, roll });
+    }
+    if let Some(gravity) = map.world.gravity {
+        UWorld::set_gravity(gravity);
+    }
+    if let Some(kill_z) = map.world.kill_z {
+        UWorld::set_kill_z(kill_z);
     }
+    if let Some(time_of_day) = map.world.time_of_day {
+        UWorld::set_time_of_day(time_of_day);
+    }
+
+    set_cluster_triggers(map);
 
     UeScope::with(|scope| {
-        let levels = LEVELS.lock().unwrap();
+        let mut levels = LEVELS.lock().unwrap();
         assert_eq!(map.clusters.len(), levels.len());
+        for (cluster_index, cluster) in map.clusters.iter().enumerate() {
+            let index = |element_type| ElementIndex { cluster_index, element_type, element_index: 0 };
+            let template_platform = get_indexed_actor(scope, &levels, template_index(index(ElementType::Platform)));
+            let template_cube = get_indexed_actor(scope, &levels, template_index(index(ElementType::Cube)));
+            let template_button = get_indexed_actor(scope, &levels, template_index(index(ElementType::Button)));
+            // every cluster has platforms, whose parent clones are attached to such that they rise with the cluster
+            let attach_reference = get_indexed_actor(scope, &levels, index(ElementType::Platform));
+            let orig = &ORIGINAL_MAP.clusters[cluster_index];
+            let level = &mut levels[cluster_index];
+            let level_wrapper = scope.get(level.level);
+            sync_element_count(scope, &mut level.platforms, orig.platforms.len(), cluster.platforms.len(), &template_platform, &attach_reference,
+                |p| level_wrapper.push_platform(p), |len| level_wrapper.truncate_platforms(len));
+            sync_element_count(scope, &mut level.cubes, orig.cubes.len(), cluster.cubes.len(), &template_cube, &attach_reference,
+                |c| level_wrapper.push_cube(c), |len| level_wrapper.truncate_cubes(len));
+            sync_element_count(scope, &mut level.buttons, orig.buttons.len(), cluster.buttons.len(), &template_button, &attach_reference,
+                |b| level_wrapper.push_button(b), |len| level_wrapper.truncate_buttons(len));
+            // lifts, pipes and springpads can't be cloned as they depend on other actors of the map
+            hide_removed_elements(scope, "lifts", cluster_index, &level.lifts, cluster.lifts.len());
+            hide_removed_elements(scope, "pipes", cluster_index, &level.pipes, cluster.pipes.len());
+            hide_removed_elements(scope, "springpads", cluster_index, &level.springpads, cluster.springpads.len());
+        }
         for (cluster_index, (level, cluster)) in levels.iter().zip(&map.clusters).enumerate() {
             let level_wrapper = scope.get(level.level);
             let (rx, ry, _) = level_wrapper.source_location();
@@ -1374,17 +2017,317 @@ pub fn apply_map_internal(map: &RefunctMap) {
                 .chain(level.springpads.iter().zip(&cluster.springpads).enumerate().map(|i| (i.0, ElementType::Springpad)))
                 .for_each(|(element_index, element_type)| {
                     let index = ElementIndex { cluster_index, element_type, element_index };
-                    set_element(scope, &levels, &map, index);
+                    set_element(scope, &levels, index, &get_indexed_element(map, index));
                 });
         }
     })
 }
+fn set_element(scope: &UeScope, levels: &[Level], index: ElementIndex, target: &Element) {
+    let level = scope.get(levels[index.cluster_index].level);
+    let actor = get_indexed_actor(scope, levels, index);
+    let orig = get_indexed_element(&*ORIGINAL_MAP, template_index(index));
+    let (_, _, rz) = level.relative_location();
+    let (rpitch, ryaw, rroll) = level.relative_rotation();
+    let target_location = FVector { x: target.x, y: target.y, z: target.z + rz };
+    let target_rotation = FRotator { pitch: target.pitch + rpitch, yaw: target.yaw + ryaw, roll: target.roll + rroll };
+    let target_scale = FVector { x: target.sizex / orig.sizex, y: target.sizey / orig.sizey, z: target.sizez / orig.sizez };
+    USceneComponent::set_world_location_and_rotation(target_location, target_rotation, &actor);
+    USceneComponent::set_world_scale(target_scale, &actor);
+    if actor.is_hidden_in_game() != target.hidden {
+        actor.set_hidden_in_game(target.hidden);
+    }
+    set_element_params(scope, levels, index, target);
+}
+/// Read the parameters of lifts, pipes and springpads, which are `None` if they can't be found on the actor
+fn read_element_params(scope: &UeScope, levels: &[Level], clusters: &mut [Cluster]) {
+    for (level, cluster) in levels.iter().zip(clusters) {
+        for (springpad, element) in level.springpads.iter().zip(&mut cluster.springpads) {
+            element.launch_velocity = scope.get(springpad).launch_velocity();
+        }
+        for (pipe, element) in level.pipes.iter().zip(&mut cluster.pipes) {
+            let exit = match scope.get(pipe).exit() {
+                Some(exit) => exit,
+                None => continue,
+            };
+            element.pipe_exit = levels.iter().enumerate().find_map(|(cluster_index, level)| {
+                let pipe_index = level.pipes.iter().position(|pipe| scope.get(pipe).as_ptr() == exit.as_ptr())?;
+                Some(PipeExit { cluster_index, pipe_index })
+            });
+        }
+        for (lift, element) in level.lifts.iter().zip(&mut cluster.lifts) {
+            let lift = scope.get(lift);
+            element.lift_distance = lift.distance();
+            element.lift_speed = lift.speed();
+        }
+    }
+}
+/// Set the parameters of lifts, pipes and springpads, keeping the current ones if they are `None`
+fn set_element_params(scope: &UeScope, levels: &[Level], index: ElementIndex, element: &Element) {
+    let level = &levels[index.cluster_index];
+    match index.element_type {
+        ElementType::Springpad => if let Some(velocity) = element.launch_velocity {
+            if !scope.get(level.springpads[index.element_index]).set_launch_velocity(velocity) {
+                log!("can't set the launch velocity of {index:?}");
+            }
+        },
+        ElementType::Pipe => if let Some(PipeExit { cluster_index, pipe_index }) = element.pipe_exit {
+            let pipe = scope.get(level.pipes[index.element_index]);
+            match levels.get(cluster_index).and_then(|level| level.pipes.get(pipe_index)) {
+                Some(exit) => if !pipe.set_exit(&scope.get(exit)) {
+                    log!("can't set the exit of {index:?}");
+                },
+                None => log!("ignoring invalid pipe exit {cluster_index} {pipe_index} of {index:?}"),
+            }
+        },
+        ElementType::Lift => {
+            let lift = scope.get(level.lifts[index.element_index]);
+            if let Some(distance) = element.lift_distance {
+                if !lift.set_distance(distance) {
+                    log!("can't set the distance of {index:?}");
+                }
+            }
+            if let Some(speed) = element.lift_speed {
+                if !lift.set_speed(speed) {
+                    log!("can't set the speed of {index:?}");
+                }
+            }
+        },
+        ElementType::Platform | ElementType::Cube | ElementType::Button => (),
+    }
+}
+struct ClusterTriggers {
+    triggers: Vec<ClusterTrigger>,
+    /// triggers whose button has already been pressed since the last new game
+    fired: Vec<bool>,
+}
+static CLUSTER_TRIGGERS: Mutex<ClusterTriggers> = Mutex::new(ClusterTriggers { triggers: Vec::new(), fired: Vec::new() });
+/// `LevelIndex` of clusters waiting for their trigger.
+///
+/// `BP_LevelRoot_C` doesn't rise on a level-change event, but checks every tick whether the game's level
+/// is at least its `LevelIndex`, which is its cluster index by default.
+/// That's also why `Tas::set_level(29)` in the "All Buttons" new game action raises all clusters at once.
+/// Setting `LevelIndex` to the current level thus raises a cluster, and this value keeps it down.
+const UNREACHABLE_LEVEL_INDEX: i32 = i32::MAX;
+
+fn set_cluster_triggers(map: &RefunctMap) {
+    let valid = |trigger: &&ClusterTrigger| {
+        let button_exists = map.clusters.get(trigger.button_cluster_index)
+            .map_or(false, |cluster| trigger.button_index < cluster.buttons.len());
+        let clusters_exist = trigger.clusters.iter().all(|&cluster_index| cluster_index < map.clusters.len());
+        if !button_exists || !clusters_exist {
+            log!("ignoring invalid cluster trigger {trigger:?}");
+        }
+        button_exists && clusters_exist
+    };
+    let triggers: Vec<_> = map.triggers.iter().filter(valid).cloned().collect();
+    let levels = LEVELS.lock().unwrap();
+    let mut cluster_triggers = CLUSTER_TRIGGERS.lock().unwrap();
+    cluster_triggers.fired = vec![false; triggers.len()];
+    cluster_triggers.triggers = triggers;
+    // always reset the level indices, as the previous map may have had triggers
+    reset_level_indices(&levels, &cluster_triggers);
+}
+/// Prevent clusters raised by triggers from rising in the game's order and reset all triggers.
+///
+/// Called from the new-game hook, independent of whether a script is running.
+pub(in crate::threads::ue) fn arm_cluster_triggers() {
+    // lock order: `LEVELS` before `CLUSTER_TRIGGERS`
+    let levels = LEVELS.lock().unwrap();
+    let mut cluster_triggers = CLUSTER_TRIGGERS.lock().unwrap();
+    if cluster_triggers.triggers.is_empty() {
+        return;
+    }
+    cluster_triggers.fired.iter_mut().for_each(|fired| *fired = false);
+    reset_level_indices(&levels, &cluster_triggers);
+}
+fn reset_level_indices(levels: &[Level], cluster_triggers: &ClusterTriggers) {
+    UeScope::with(|scope| {
+        for (cluster_index, level) in levels.iter().enumerate() {
+            let triggered = cluster_triggers.triggers.iter().any(|trigger| trigger.clusters.contains(&cluster_index));
+            let level_index = match triggered {
+                true => UNREACHABLE_LEVEL_INDEX,
+                false => cluster_index.try_into().unwrap(),
+            };
+            scope.get(level.level).set_level_index(level_index);
+        }
+    })
+}
+/// Raise the clusters of all triggers of the pressed button, which haven't been fired yet.
+///
+/// Called from the element-pressed hook, independent of whether a script is running.
+pub(in crate::threads::ue) fn fire_cluster_triggers(index: ElementIndex) {
+    if !matches!(index.element_type, ElementType::Button) {
+        return;
+    }
+    // lock order: `LEVELS` before `CLUSTER_TRIGGERS`
+    let levels = LEVELS.lock().unwrap();
+    let mut cluster_triggers = CLUSTER_TRIGGERS.lock().unwrap();
+    let ClusterTriggers { triggers, fired } = &mut *cluster_triggers;
+    let current_level = LevelState::get_level();
+    UeScope::with(|scope| {
+        for (trigger, fired) in triggers.iter().zip(fired) {
+            if *fired || trigger.button_cluster_index != index.cluster_index || trigger.button_index != index.element_index {
+                continue;
+            }
+            *fired = true;
+            for &cluster_index in &trigger.clusters {
+                // the level root sees that its level is reached on its next tick, see `UNREACHABLE_LEVEL_INDEX`
+                scope.get(levels[cluster_index].level).set_level_index(current_level);
+            }
+        }
+    })
+}
+
 #[rebo::function("Tas::apply_map")]
 fn apply_map(map: RefunctMap) {
     apply_map_internal(&map)
 }
+
+#[derive(Debug, Clone)]
+struct ElementChange {
+    index: ElementIndex,
+    old: Element,
+    new: Element,
+}
+#[derive(Debug, Clone)]
+enum JournalEntry {
+    Elements(Vec<ElementChange>),
+    /// anything except for existing elements changed, e.g. elements were added or a cluster's rise speed changed,
+    /// which requires the whole map to be applied
+    Map { old: RefunctMap, new: RefunctMap },
+}
+struct MapJournal {
+    /// the map as it was last applied or read from the world, `None` if no map has been applied yet
+    applied: Option<RefunctMap>,
+    /// `applied` when `Tas::map_begin_edit` was called
+    edit_base: Option<RefunctMap>,
+    undo: Vec<JournalEntry>,
+    redo: Vec<JournalEntry>,
+}
+static MAP_JOURNAL: Mutex<MapJournal> = Mutex::new(MapJournal { applied: None, edit_base: None, undo: Vec::new(), redo: Vec::new() });
+#[derive(Debug, Clone, rebo::ExternalType)]
+enum MapEditError {
+    /// `Tas::map_commit` was called without `Tas::map_begin_edit`
+    NoEditInProgress,
+    NothingToUndo,
+    NothingToRedo,
+}
+
+impl MapJournal {
+    /// Apply the new (or old if `undo`) side of the entry and return the resulting map
+    fn apply(&mut self, entry: &JournalEntry, undo: bool) -> RefunctMap {
+        match entry {
+            JournalEntry::Elements(changes) => {
+                let applied = self.applied.as_mut().unwrap();
+                UeScope::with(|scope| {
+                    let levels = LEVELS.lock().unwrap();
+                    for ElementChange { index, old, new } in changes {
+                        let target = if undo { old } else { new };
+                        set_element(scope, &levels, *index, target);
+                        applied.clusters[index.cluster_index].elements_mut(index.element_type)[index.element_index] = target.clone();
+                    }
+                });
+            }
+            JournalEntry::Map { old, new } => {
+                let target = if undo { old } else { new };
+                apply_map_without_history(target);
+                self.applied = Some(target.clone());
+            }
+        }
+        self.applied.clone().unwrap()
+    }
+}
+/// Everything of the map except for the properties of its elements
+fn map_structure(map: &RefunctMap) -> serde_json::Value {
+    let mut value = serde_json::to_value(map).unwrap();
+    for cluster in value["clusters"].as_array_mut().unwrap() {
+        for list in ["platforms", "cubes", "buttons", "lifts", "pipes", "springpads"] {
+            let len = cluster[list].as_array().unwrap().len();
+            cluster[list] = len.into();
+        }
+    }
+    value
+}
+/// Changed elements from `old` to `new`, `None` if anything else changed
+fn element_changes(old: &RefunctMap, new: &RefunctMap) -> Option<Vec<ElementChange>> {
+    if map_structure(old) != map_structure(new) {
+        return None;
+    }
+    let mut changes = Vec::new();
+    for (cluster_index, (old_cluster, new_cluster)) in old.clusters.iter().zip(&new.clusters).enumerate() {
+        for element_type in [ElementType::Platform, ElementType::Cube, ElementType::Button, ElementType::Lift, ElementType::Pipe, ElementType::Springpad] {
+            let elements = old_cluster.elements(element_type).iter().zip(new_cluster.elements(element_type));
+            for (element_index, (old, new)) in elements.enumerate() {
+                if old != new {
+                    let index = ElementIndex { cluster_index, element_type, element_index };
+                    changes.push(ElementChange { index, old: old.clone(), new: new.clone() });
+                }
+            }
+        }
+    }
+    Some(changes)
+}
+
+/// Start an edit of the currently applied map, which is finished with `Tas::map_commit`.
+///
+/// The map is read from the world, as e.g. `Tas::cluster_raise` or the game itself may have changed it
+/// since the last commit.
+#[rebo::function("Tas::map_begin_edit")]
+fn map_begin_edit() {
+    let mut journal = MAP_JOURNAL.lock().unwrap();
+    let mut base = get_current_map(false);
+    // the world doesn't know the map's metadata and time of day
+    if let Some(applied) = &journal.applied {
+        base.metadata = applied.metadata.clone();
+        base.world.time_of_day = applied.world.time_of_day;
+    }
+    journal.applied = Some(base.clone());
+    journal.edit_base = Some(base);
+}
+/// Apply the edited map and record it in the history.
+///
+/// If only existing elements changed, only their actors are updated.
+#[rebo::function("Tas::map_commit")]
+fn map_commit(map: RefunctMap) -> Result<(), MapEditError> {
+    let mut journal = MAP_JOURNAL.lock().unwrap();
+    let base = journal.edit_base.take().ok_or(MapEditError::NoEditInProgress)?;
+    let entry = match element_changes(&base, &map) {
+        Some(changes) if changes.is_empty() => return Ok(()),
+        Some(changes) => JournalEntry::Elements(changes),
+        None => JournalEntry::Map { old: base, new: map },
+    };
+    journal.apply(&entry, false);
+    journal.undo.push(entry);
+    journal.redo.clear();
+    Ok(())
+}
+/// Revert the last commit, returning the resulting map
+#[rebo::function("Tas::map_undo")]
+fn map_undo() -> Result<RefunctMap, MapEditError> {
+    let mut journal = MAP_JOURNAL.lock().unwrap();
+    let entry = journal.undo.pop().ok_or(MapEditError::NothingToUndo)?;
+    let map = journal.apply(&entry, true);
+    journal.redo.push(entry);
+    if journal.edit_base.is_some() {
+        journal.edit_base = Some(map.clone());
+    }
+    Ok(map)
+}
+/// Reapply the last undone commit, returning the resulting map
+#[rebo::function("Tas::map_redo")]
+fn map_redo() -> Result<RefunctMap, MapEditError> {
+    let mut journal = MAP_JOURNAL.lock().unwrap();
+    let entry = journal.redo.pop().ok_or(MapEditError::NothingToRedo)?;
+    let map = journal.apply(&entry, false);
+    journal.undo.push(entry);
+    if journal.edit_base.is_some() {
+        journal.edit_base = Some(map.clone());
+    }
+    Ok(map)
+}
 #[rebo::function("Tas::apply_map_cluster_speeds")]
 fn apply_map_cluster_speeds(map: RefunctMap) {
+    FROZEN_CLUSTERS.lock().unwrap().clear();
     UeScope::with(|scope| {
         let levels = LEVELS.lock().unwrap();
         assert_eq!(map.clusters.len(), levels.len());
@@ -1399,7 +2342,121 @@ fn get_looked_at_element_index() -> Option<ElementIndex> {
     let intersected = KismetSystemLibrary::line_trace_single(AMyCharacter::get_player());
     try_find_element_index(intersected as *mut UObject)
 }
+#[derive(Debug, Clone, rebo::ExternalType)]
+struct TraceHit {
+    location: Location,
+    impact_normal: Vector,
+    distance: f32,
+    /// empty if the actor was destroyed in the meantime
+    actor_name: String,
+    element_index: Option<ElementIndex>,
+}
+impl From<FHitResult> for TraceHit {
+    fn from(hit: FHitResult) -> Self {
+        let actor = hit.actor();
+        let (actor_name, element_index) = match actor.is_null() {
+            true => (String::new(), None),
+            false => (
+                unsafe { ObjectWrapper::new(actor as *mut UObject) }.name(),
+                try_find_element_index(actor as *mut UObject),
+            ),
+        };
+        TraceHit {
+            location: Location { x: hit.location.x, y: hit.location.y, z: hit.location.z },
+            impact_normal: Vector { x: hit.impact_normal.x, y: hit.impact_normal.y, z: hit.impact_normal.z },
+            distance: hit.distance,
+            actor_name,
+            element_index,
+        }
+    }
+}
+/// `channel` is the trace channel, 0 is `Visibility` and 1 is `Camera`.
+#[rebo::function("Tas::line_trace")]
+fn line_trace(start: Location, end: Location, channel: u8) -> Option<TraceHit> {
+    let start = FVector { x: start.x, y: start.y, z: start.z };
+    let end = FVector { x: end.x, y: end.y, z: end.z };
+    KismetSystemLibrary::line_trace(AMyCharacter::get_player(), start, end, channel, EDrawDebugTraceType::None)
+        .map(TraceHit::from)
+}
+/// Sweep the player's collision capsule from `start` to `end`, e.g. to check where the player would land.
+/// The locations are the center of the capsule like `Tas::get_location`.
+#[rebo::function("Tas::capsule_trace")]
+fn capsule_trace(start: Location, end: Location, channel: u8) -> Option<TraceHit> {
+    let player = AMyCharacter::get_player();
+    let (radius, half_height) = player.capsule_size();
+    let start = FVector { x: start.x, y: start.y, z: start.z };
+    let end = FVector { x: end.x, y: end.y, z: end.z };
+    KismetSystemLibrary::capsule_trace(player, start, end, radius, half_height, channel)
+        .map(TraceHit::from)
+}
 
+/// Spawn or destroy clones of original elements such that the list contains `target_count` elements,
+/// or fewer if UE refuses to spawn a clone.
+/// Original elements are never destroyed, but hidden if they are not part of the target map.
+fn sync_element_count<'a, T: UeObjectWrapperType>(
+    scope: &'a UeScope, list: &mut Vec<ObjectIndex<T>>, original_count: usize, target_count: usize,
+    template: &ActorWrapper<'a>, attach_reference: &ActorWrapper<'a>,
+    push: impl Fn(&T::UeObjectWrapper<'a>), truncate: impl Fn(usize),
+) where T::UeObjectWrapper<'a>: Deref<Target = ActorWrapper<'a>> {
+    while list.len() < target_count {
+        // the location is overwritten afterwards by `set_element`
+        let (x, y, z) = template.absolute_location();
+        let (pitch, yaw, roll) = template.relative_rotation();
+        let ptr = UWorld::spawn_actor_from_template(template, FVector { x, y, z }, FRotator { pitch, yaw, roll });
+        if ptr.is_null() {
+            // like elements that can't be cloned, the missing ones are left out of the cluster
+            log!("couldn't spawn a clone of {}, ignoring {} additional ones", template.name(), target_count - list.len());
+            break;
+        }
+        let clone = unsafe { ActorWrapper::new(ptr) }.upcast::<T::UeObjectWrapper<'a>>();
+        clone.attach_like(attach_reference);
+        push(&clone);
+        list.push(scope.object_index(&clone));
+    }
+    while list.len() > target_count.max(original_count) {
+        let clone = list.pop().unwrap();
+        // the clone may have been destroyed by the game in the meantime
+        if let Ok(clone) = scope.try_get(clone) {
+            UWorld::destroy_spawned_actor(&clone);
+        }
+    }
+    truncate(list.len());
+    hide_originals_beyond(scope, list, target_count);
+}
+fn hide_removed_elements<'a, T: UeObjectWrapperType>(scope: &'a UeScope, name: &str, cluster_index: usize, list: &[ObjectIndex<T>], target_count: usize)
+where T::UeObjectWrapper<'a>: Deref<Target = ActorWrapper<'a>> {
+    if target_count > list.len() {
+        log!("can't add {name} to cluster {cluster_index}, ignoring {} additional ones", target_count - list.len());
+    }
+    hide_originals_beyond(scope, list, target_count);
+}
+/// Original elements not contained in the target map anymore are hidden.
+fn hide_originals_beyond<'a, T: UeObjectWrapperType>(scope: &'a UeScope, list: &[ObjectIndex<T>], target_count: usize)
+where T::UeObjectWrapper<'a>: Deref<Target = ActorWrapper<'a>> {
+    for original in list.iter().skip(target_count) {
+        let original = scope.get(original);
+        if !original.is_hidden_in_game() {
+            original.set_hidden_in_game(true);
+        }
+    }
+}
+/// Index of the original element an element is cloned from.
+///
+/// Elements added to a cluster are clones of the first element of the same type in that cluster.
+/// If the cluster doesn't have an original element of that type, the first one of any cluster is used.
+fn template_index(index: ElementIndex) -> ElementIndex {
+    let orig = &*ORIGINAL_MAP;
+    let count = |cluster: &Cluster| cluster.elements(index.element_type).len();
+    if index.element_index < count(&orig.clusters[index.cluster_index]) {
+        return index;
+    }
+    let cluster_index = if count(&orig.clusters[index.cluster_index]) > 0 {
+        index.cluster_index
+    } else {
+        orig.clusters.iter().position(|cluster| count(cluster) > 0).unwrap()
+    };
+    ElementIndex { cluster_index, element_type: index.element_type, element_index: 0 }
+}
 fn get_indexed_element(map: &RefunctMap, index: ElementIndex) -> Element {
     let level = &map.clusters[index.cluster_index];
     match index.element_type {
@@ -1424,13 +2481,13 @@ fn get_indexed_actor<'a>(scope: &'a UeScope, levels: &[Level], index: ElementInd
 }
 
 #[derive(Debug, Clone, rebo::ExternalType)]
-struct Bounds {
-    originx: f32,
-    originy: f32,
-    originz: f32,
-    extentx: f32,
-    extenty: f32,
-    extentz: f32,
+pub(super) struct Bounds {
+    pub(super) originx: f32,
+    pub(super) originy: f32,
+    pub(super) originz: f32,
+    pub(super) extentx: f32,
+    pub(super) extenty: f32,
+    pub(super) extentz: f32,
 }
 
 #[rebo::function("Tas::get_element_bounds")]
@@ -1581,3 +2638,47 @@ fn set_input_mode_ui_only() {
 fn flush_pressed_keys() {
     AMyCharacter::flush_pressed_keys();
 }
+#[derive(Debug, Clone, rebo::ExternalType)]
+enum DumpSdkError {
+    /// error message
+    CreateFailed(String),
+    /// error message
+    WriteFailed(String),
+}
+/// Dump all classes, structs and functions to a JSON file.
+/// Relative paths are resolved from the tool's working directory.
+#[rebo::function("Tas::dump_sdk")]
+fn dump_sdk(path: String) -> Result<(), DumpSdkError> {
+    let working_dir = STATE.lock().unwrap().as_ref().unwrap().working_dir.clone();
+    let path = match working_dir {
+        Some(dir) => PathBuf::from(dir).join(path),
+        None => PathBuf::from(path),
+    };
+    dump_sdk_to_file(&path).map(drop)
+}
+/// Handle the tool's dump-sdk command, reporting back to the tool once done.
+pub(super) fn dump_sdk_for_tool(path: String) {
+    let msg = match dump_sdk_to_file(Path::new(&path)) {
+        Ok(msg) => msg,
+        Err(DumpSdkError::CreateFailed(e)) => format!("Couldn't create SDK dump file {path}: {e}"),
+        Err(DumpSdkError::WriteFailed(e)) => format!("Couldn't write SDK dump file {path}: {e}"),
+    };
+    let state = STATE.lock().unwrap();
+    let rebo_stream_tx = &state.as_ref().unwrap().rebo_stream_tx;
+    rebo_stream_tx.send(ReboToStream::Print(msg)).unwrap();
+    rebo_stream_tx.send(ReboToStream::MiDone).unwrap();
+}
+fn dump_sdk_to_file(path: &Path) -> Result<String, DumpSdkError> {
+    let dump = UeScope::with(|scope| native::dump_sdk(scope));
+    let file = File::create(path).map_err(|e| {
+        log!("Couldn't create SDK dump file {}: {e}", path.display());
+        DumpSdkError::CreateFailed(e.to_string())
+    })?;
+    serde_json::to_writer_pretty(file, &dump).map_err(|e| {
+        log!("Couldn't write SDK dump file {}: {e}", path.display());
+        DumpSdkError::WriteFailed(e.to_string())
+    })?;
+    let msg = format!("Dumped {} classes, {} structs and {} functions to {}", dump.classes.len(), dump.structs.len(), dump.functions.len(), path.display());
+    log!("{msg}");
+    Ok(msg)
+}
@@ -0,0 +1,344 @@
+use std::cell::Cell;
+use std::ffi::c_void;
+use crate::native::{ue::{FName, FString}, ArrayPropertyWrapper, ArrayWrapper, BoolValueWrapper, DynamicValue, EnumValueWrapper, FunctionWrapper, MapWrapper, ObjectIndex, ObjectPropertyWrapper, ObjectWrapper, ObjectWrapperType, ProcessEventFilter, SetWrapper, SizedArrayElement, StructPropertyWrapper, StructValueWrapper, UeScope, UFunction, UnknownEnumKind, UObject};
+
+// EPropertyFlags
+const CPF_OUT_PARM: u64 = 0x100;
+const CPF_RETURN_PARM: u64 = 0x400;
+
+/// Handle to any UObject, invalidated once the object is destroyed
+#[derive(rebo::ExternalType, Debug, Clone, Copy)]
+pub struct UeObject {
+    pub internal_index: i32,
+    pub serial_number: i32,
+}
+
+#[derive(rebo::ExternalType, Debug, Clone)]
+pub enum UeValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Name(String),
+    /// name of the enumerator
+    Enum(String),
+    Object(Option<UeObject>),
+    Struct(Vec<UeField>),
+    /// TArray or TSet
+    Array(Vec<UeValue>),
+    Map(Vec<UeMapEntry>),
+}
+#[derive(rebo::ExternalType, Debug, Clone)]
+pub struct UeField {
+    pub name: String,
+    pub value: UeValue,
+}
+#[derive(rebo::ExternalType, Debug, Clone)]
+pub struct UeMapEntry {
+    pub key: UeValue,
+    pub value: UeValue,
+}
+
+/// A `UObject::ProcessEvent` call matching one of the filters registered with `Tas::add_ue_event_filter`
+#[derive(rebo::ExternalType, Debug, Clone)]
+pub struct UeFunctionCall {
+    pub object: UeObject,
+    pub object_name: String,
+    pub class_name: String,
+    pub function_name: String,
+    /// input parameters, parameters of unsupported types are skipped
+    pub params: Vec<UeField>,
+}
+
+#[derive(rebo::ExternalType, Debug, Clone)]
+pub enum ReflectionError {
+    ObjectInvalidated,
+    /// property path
+    PropertyNotFound(String),
+    /// function name
+    FunctionNotFound(String),
+    /// property path of the null object
+    NullObject(String),
+    /// property path, index
+    IndexOutOfBounds(String, i64),
+    /// description
+    TypeMismatch(String),
+    /// property kind
+    Unsupported(String),
+}
+
+#[rebo::function("Tas::find_objects")]
+pub fn find_objects(class_name: String) -> Vec<UeObject> {
+    UeScope::with(|scope| {
+        scope.iter_global_object_array()
+            .map(|item| item.object())
+            .filter(|object| object.class().extends_from(&class_name) && !object.name().starts_with("Default__"))
+            .map(|object| to_ue_object(scope, &object))
+            .collect()
+    })
+}
+#[rebo::function("Tas::get_object_name")]
+pub fn get_object_name(object: UeObject) -> Result<String, ReflectionError> {
+    UeScope::with(|scope| Ok(resolve(scope, object)?.name()))
+}
+#[rebo::function("Tas::get_object_class_name")]
+pub fn get_object_class_name(object: UeObject) -> Result<String, ReflectionError> {
+    UeScope::with(|scope| Ok(resolve(scope, object)?.class().name()))
+}
+/// Get the property at the `.`-separated `path` (e.g. `RootComponent.RelativeLocation.X`).
+/// Array elements can be accessed with `Name[index]`.
+#[rebo::function("Tas::get_property")]
+pub fn get_property(object: UeObject, path: String) -> Result<UeValue, ReflectionError> {
+    UeScope::with(|scope| {
+        let value = resolve_path(resolve(scope, object)?, &path)?;
+        read_value(scope, value)
+    })
+}
+#[rebo::function("Tas::set_property")]
+pub fn set_property(object: UeObject, path: String, value: UeValue) -> Result<(), ReflectionError> {
+    UeScope::with(|scope| {
+        let target = resolve_path(resolve(scope, object)?, &path)?;
+        write_value(scope, target, &value)
+    })
+}
+/// Call the UFunction `name` on the object, returning all out-parameters and the return value.
+#[rebo::function("Tas::call_function")]
+pub fn call_function(object: UeObject, name: String, args: Vec<UeField>) -> Result<Vec<UeField>, ReflectionError> {
+    UeScope::with(|scope| {
+        let object = resolve(scope, object)?;
+        let function = object.class().find_function(&name)
+            .ok_or_else(|| ReflectionError::FunctionNotFound(name.clone()))?;
+        let params = function.create_argument_struct();
+        for arg in &args {
+            let param = params.try_get_field(&arg.name)
+                .ok_or_else(|| ReflectionError::PropertyNotFound(arg.name.clone()))?;
+            write_value(scope, param, &arg.value)?;
+        }
+        unsafe { function.call(object.as_ptr(), &params) };
+
+        let mut out = Vec::new();
+        for param in function.iter_params() {
+            if param.property_flags() & (CPF_OUT_PARM | CPF_RETURN_PARM) != 0 {
+                let value = read_value(scope, params.get_field(&param.name()))?;
+                out.push(UeField { name: param.name(), value });
+            }
+        }
+        // the parameter struct doesn't drop its fields, free the strings allocated for / by the call
+        for param in function.iter_params() {
+            if <&Cell<FString>>::can_be_created_from(&param) {
+                params.get_field(&param.name()).unwrap::<&Cell<FString>>().set(FString::new());
+            }
+        }
+        Ok(out)
+    })
+}
+
+/// Report `UObject::ProcessEvent` calls on objects of a class matching `class_glob` (including super classes)
+/// with a function name matching `function_glob` to `on_ue_event`.
+/// Globs support `*` and `?`, e.g. `Tas::add_ue_event_filter("BP_*_C", "*Pickup*")`.
+#[rebo::function("Tas::add_ue_event_filter")]
+pub fn add_ue_event_filter(class_glob: String, function_glob: String) {
+    crate::native::add_process_event_filter(ProcessEventFilter { class_glob, function_glob });
+}
+#[rebo::function("Tas::clear_ue_event_filters")]
+pub fn clear_ue_event_filters() {
+    crate::native::clear_process_event_filters();
+}
+
+pub(in crate::threads::ue) fn decode_function_call(object: *mut UObject, function: *mut UFunction, params: *mut c_void) -> UeFunctionCall {
+    UeScope::with(|scope| {
+        let object = unsafe { ObjectWrapper::new(object) };
+        let function = unsafe { FunctionWrapper::new(function) };
+        let decoded_params = match params.is_null() {
+            true => Vec::new(),
+            false => {
+                let params = unsafe { StructValueWrapper::new(params, function.upcast()) };
+                function.iter_params()
+                    .filter(|param| param.property_flags() & CPF_RETURN_PARM == 0)
+                    .filter_map(|param| {
+                        let value = read_value(scope, params.get_field(&param.name())).ok()?;
+                        Some(UeField { name: param.name(), value })
+                    }).collect()
+            }
+        };
+        UeFunctionCall {
+            object: to_ue_object(scope, &object),
+            object_name: object.name(),
+            class_name: object.class().name(),
+            function_name: function.name(),
+            params: decoded_params,
+        }
+    })
+}
+
+fn to_ue_object(scope: &UeScope, object: &ObjectWrapper) -> UeObject {
+    let index = scope.object_index(object);
+    UeObject { internal_index: index.internal_index(), serial_number: index.serial_number() }
+}
+fn resolve(scope: &UeScope, object: UeObject) -> Result<ObjectWrapper<'_>, ReflectionError> {
+    let index = ObjectIndex::<ObjectWrapperType>::from_raw(object.internal_index, object.serial_number);
+    scope.try_get(index).map_err(|_| ReflectionError::ObjectInvalidated)
+}
+
+fn resolve_path<'a>(object: ObjectWrapper<'a>, path: &str) -> Result<DynamicValue<'a>, ReflectionError> {
+    let mut current: Option<DynamicValue<'a>> = None;
+    let mut walked = String::new();
+    for segment in path.split('.') {
+        let (name, index) = match segment.split_once('[') {
+            Some((name, index)) => {
+                let index = index.strip_suffix(']').and_then(|i| i.parse::<usize>().ok())
+                    .ok_or_else(|| ReflectionError::TypeMismatch(format!("invalid array index in {segment}")))?;
+                (name, Some(index))
+            }
+            None => (segment, None),
+        };
+        let parent = walked.clone();
+        if !walked.is_empty() {
+            walked.push('.');
+        }
+        walked.push_str(name);
+
+        let field = match current.take() {
+            None => object.try_get_field(name),
+            Some(value) => {
+                if ObjectWrapper::can_be_created_from(&value.prop()) {
+                    let object = value.unwrap_object().ok_or_else(|| ReflectionError::NullObject(parent))?;
+                    object.try_get_field(name)
+                } else if StructValueWrapper::can_be_created_from(&value.prop()) {
+                    value.try_field(name)
+                } else {
+                    return Err(ReflectionError::TypeMismatch(format!("{parent} of type {} doesn't have fields", value.prop().type_name())));
+                }
+            }
+        };
+        let mut field = field.ok_or_else(|| ReflectionError::PropertyNotFound(walked.clone()))?;
+
+        if let Some(index) = index {
+            if field.prop().try_upcast::<ArrayPropertyWrapper>().is_none() {
+                return Err(ReflectionError::TypeMismatch(format!("{walked} of type {} isn't an array", field.prop().type_name())));
+            }
+            let array = field.unwrap::<ArrayWrapper<DynamicValue>>();
+            field = array.get(index).ok_or_else(|| ReflectionError::IndexOutOfBounds(walked.clone(), index as i64))?;
+            walked.push_str(&format!("[{index}]"));
+        }
+        current = Some(field);
+    }
+    current.ok_or_else(|| ReflectionError::PropertyNotFound(path.to_string()))
+}
+
+fn read_value(scope: &UeScope, value: DynamicValue) -> Result<UeValue, ReflectionError> {
+    let prop = value.prop();
+    let kind = prop.property_kind();
+    let res = match kind.as_str() {
+        "BoolProperty" => UeValue::Bool(value.unwrap::<BoolValueWrapper>().get()),
+        "Int8Property" => UeValue::Int(value.unwrap::<i8>().into()),
+        "Int16Property" => UeValue::Int(value.unwrap::<i16>().into()),
+        "IntProperty" => UeValue::Int(value.unwrap::<i32>().into()),
+        "Int64Property" => UeValue::Int(value.unwrap::<i64>()),
+        "UInt16Property" => UeValue::Int(value.unwrap::<u16>().into()),
+        "UInt32Property" => UeValue::Int(value.unwrap::<u32>().into()),
+        "UInt64Property" => UeValue::Int(value.unwrap::<u64>() as i64),
+        "FloatProperty" => UeValue::Float(value.unwrap::<f32>().into()),
+        "DoubleProperty" => UeValue::Float(value.unwrap::<f64>()),
+        "StrProperty" => UeValue::String(unsafe { (*value.unwrap::<&Cell<FString>>().as_ptr()).to_string_lossy() }),
+        "NameProperty" => UeValue::Name(value.unwrap::<FName>().to_string_lossy()),
+        "ByteProperty" | "EnumProperty" if EnumValueWrapper::can_be_created_from(&prop) => {
+            let enum_value = value.unwrap::<EnumValueWrapper>();
+            let int = enum_value.get().map_err(|UnknownEnumKind(kind)| ReflectionError::Unsupported(kind))?;
+            match enum_value.enum_().and_then(|enum_| enum_.name_of_value(int)) {
+                Some(name) => UeValue::Enum(name),
+                None => UeValue::Int(int),
+            }
+        }
+        "ByteProperty" => UeValue::Int(value.unwrap::<u8>().into()),
+        _ if ObjectWrapper::can_be_created_from(&prop) => {
+            UeValue::Object(value.unwrap_object().map(|object| to_ue_object(scope, &object)))
+        }
+        "StructProperty" => {
+            let struct_value = value.unwrap::<StructValueWrapper>();
+            let struct_ = prop.upcast::<StructPropertyWrapper>().struct_();
+            let fields = struct_.iter_properties()
+                .map(|field| Ok(UeField { name: field.name(), value: read_value(scope, struct_value.get_field(&field.name()))? }))
+                .collect::<Result<_, _>>()?;
+            UeValue::Struct(fields)
+        }
+        "ArrayProperty" => UeValue::Array((&value.unwrap::<ArrayWrapper<DynamicValue>>()).into_iter()
+            .map(|element| read_value(scope, element))
+            .collect::<Result<_, _>>()?),
+        "SetProperty" => UeValue::Array(value.unwrap::<SetWrapper<DynamicValue>>().iter()
+            .map(|element| read_value(scope, element))
+            .collect::<Result<_, _>>()?),
+        "MapProperty" => UeValue::Map(value.unwrap::<MapWrapper<DynamicValue, DynamicValue>>().iter()
+            .map(|(key, value)| Ok(UeMapEntry { key: read_value(scope, key)?, value: read_value(scope, value)? }))
+            .collect::<Result<_, _>>()?),
+        _ => return Err(ReflectionError::Unsupported(kind)),
+    };
+    Ok(res)
+}
+
+fn write_value(scope: &UeScope, target: DynamicValue, value: &UeValue) -> Result<(), ReflectionError> {
+    fn int<T: TryFrom<i64>>(prop_type: &str, value: i64) -> Result<T, ReflectionError> {
+        T::try_from(value).map_err(|_| ReflectionError::TypeMismatch(format!("{value} is out of range for {prop_type}")))
+    }
+    let prop = target.prop();
+    let kind = prop.property_kind();
+    let mismatch = || ReflectionError::TypeMismatch(format!("can't assign {value:?} to {} {}", prop.type_name(), prop.name()));
+    match (kind.as_str(), value) {
+        ("BoolProperty", UeValue::Bool(b)) => target.unwrap::<BoolValueWrapper>().set(*b),
+        ("Int8Property", &UeValue::Int(i)) => target.unwrap::<&Cell<i8>>().set(int(&kind, i)?),
+        ("Int16Property", &UeValue::Int(i)) => target.unwrap::<&Cell<i16>>().set(int(&kind, i)?),
+        ("IntProperty", &UeValue::Int(i)) => target.unwrap::<&Cell<i32>>().set(int(&kind, i)?),
+        ("Int64Property", &UeValue::Int(i)) => target.unwrap::<&Cell<i64>>().set(i),
+        ("UInt16Property", &UeValue::Int(i)) => target.unwrap::<&Cell<u16>>().set(int(&kind, i)?),
+        ("UInt32Property", &UeValue::Int(i)) => target.unwrap::<&Cell<u32>>().set(int(&kind, i)?),
+        ("UInt64Property", &UeValue::Int(i)) => target.unwrap::<&Cell<u64>>().set(int(&kind, i)?),
+        ("FloatProperty", &UeValue::Float(f)) => target.unwrap::<&Cell<f32>>().set(f as f32),
+        ("FloatProperty", &UeValue::Int(i)) => target.unwrap::<&Cell<f32>>().set(i as f32),
+        ("DoubleProperty", &UeValue::Float(f)) => target.unwrap::<&Cell<f64>>().set(f),
+        ("DoubleProperty", &UeValue::Int(i)) => target.unwrap::<&Cell<f64>>().set(i as f64),
+        ("StrProperty", UeValue::String(s)) => target.unwrap::<&Cell<FString>>().set(FString::from(s)),
+        ("NameProperty", UeValue::Name(s) | UeValue::String(s)) => target.unwrap::<&Cell<FName>>().set(FName::from(s)),
+        ("ByteProperty" | "EnumProperty", UeValue::Enum(name)) if EnumValueWrapper::can_be_created_from(&prop) => {
+            let enum_value = target.unwrap::<EnumValueWrapper>();
+            let enum_ = enum_value.enum_().unwrap();
+            let val = enum_.value_of_name(name)
+                .ok_or_else(|| ReflectionError::TypeMismatch(format!("{} has no enumerator {name}", enum_.name())))?;
+            enum_value.set(val).map_err(|UnknownEnumKind(kind)| ReflectionError::Unsupported(kind))?;
+        }
+        ("EnumProperty", &UeValue::Int(i)) => target.unwrap::<EnumValueWrapper>().set(i)
+            .map_err(|UnknownEnumKind(kind)| ReflectionError::Unsupported(kind))?,
+        ("ByteProperty", &UeValue::Int(i)) => target.unwrap::<&Cell<u8>>().set(int(&kind, i)?),
+        (_, UeValue::Object(object)) if ObjectWrapper::can_be_created_from(&prop) => match object {
+            None => target.set_object_null(),
+            Some(object) => {
+                let object = resolve(scope, *object)?;
+                let property_class = prop.upcast::<ObjectPropertyWrapper>().property_class();
+                if !object.class().extends_from(&property_class.name()) {
+                    return Err(ReflectionError::TypeMismatch(format!("{} doesn't extend from {}", object.class().name(), property_class.name())));
+                }
+                target.set_object(&object);
+            }
+        },
+        ("StructProperty", UeValue::Struct(fields)) => {
+            let struct_value = target.unwrap::<StructValueWrapper>();
+            for field in fields {
+                let field_value = struct_value.try_get_field(&field.name)
+                    .ok_or_else(|| ReflectionError::PropertyNotFound(format!("{}.{}", prop.name(), field.name)))?;
+                write_value(scope, field_value, &field.value)?;
+            }
+        }
+        ("ArrayProperty", UeValue::Array(values)) => {
+            let array = target.unwrap::<ArrayWrapper<DynamicValue>>();
+            // resizing would require reallocating with UE's allocator and initializing the elements
+            if array.len() != values.len() {
+                return Err(ReflectionError::TypeMismatch(format!("can't change the length of {} from {} to {}", prop.name(), array.len(), values.len())));
+            }
+            for (element, value) in (&array).into_iter().zip(values) {
+                write_value(scope, element, value)?;
+            }
+        }
+        ("SetProperty" | "MapProperty", _) => return Err(ReflectionError::Unsupported(kind)),
+        _ => return Err(mismatch()),
+    }
+    Ok(())
+}
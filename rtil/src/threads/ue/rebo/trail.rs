@@ -0,0 +1,193 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use crate::native::{AMyCharacter, HudLine, KismetSystemLibrary, UWorld, ue::FVector};
+use super::debug_draw::View;
+use super::rebo_init::{Color, Vector};
+
+static OVERLAY: Mutex<Overlay> = Mutex::new(Overlay { trail: None, trajectory: None });
+
+/// Time step of the simulated jump arc
+const TRAJECTORY_STEP: f32 = 1. / 30.;
+/// Longest arc to predict, each step is a capsule trace every tick
+const MAX_TRAJECTORY_DURATION: f32 = 10.;
+
+struct Overlay {
+    trail: Option<Trail>,
+    trajectory: Option<Trajectory>,
+}
+struct Trajectory {
+    settings: TrajectorySettings,
+    /// arc predicted at the last tick
+    points: Vec<Vector>,
+}
+struct Trail {
+    settings: TrailSettings,
+    /// player location and horizontal speed of each tick, oldest first
+    samples: VecDeque<(Vector, f32)>,
+}
+
+#[derive(Debug, Clone, Copy, rebo::ExternalType)]
+pub struct TrailSettings {
+    /// number of ticks to keep
+    length: u32,
+    /// horizontal speed drawn fully red, slower parts go over green to blue
+    max_speed: f32,
+    thickness: f32,
+}
+#[derive(Debug, Clone, Copy, rebo::ExternalType)]
+pub struct TrajectorySettings {
+    /// seconds to predict at most, up to 10
+    duration: f32,
+    color: Color,
+    thickness: f32,
+}
+#[derive(Debug, Clone, rebo::ExternalType)]
+pub enum TrailError {
+    /// `length` must be at least 1
+    InvalidLength,
+    /// `max_speed` must be greater than 0
+    InvalidMaxSpeed,
+    /// `duration` must be greater than 0 and at most 10 seconds
+    InvalidDuration,
+}
+
+/// Blue for standing still, green for half of `max_speed` and red from `max_speed` on
+fn speed_color(speed: f32, max_speed: f32) -> (f32, f32, f32, f32) {
+    let t = (speed / max_speed).clamp(0., 1.);
+    if t < 0.5 {
+        (0., t * 2., 1. - t * 2., 1.)
+    } else {
+        ((t - 0.5) * 2., 1. - (t - 0.5) * 2., 0., 1.)
+    }
+}
+
+/// Simulate the player's flight from the current velocity, or of a jump if the player is on the ground.
+///
+/// Air control is ignored, as it depends on the input during the jump.
+/// The arc ends where the player's capsule would hit something.
+fn predict_trajectory(duration: f32) -> Vec<Vector> {
+    let player = AMyCharacter::get_player();
+    let (x, y, z) = player.location();
+    // horizontal velocity doesn't change without air control
+    let (vx, vy, mut vz) = player.velocity();
+    if !player.is_falling() {
+        vz = player.movement_property("JumpZVelocity");
+    }
+    let gravity = UWorld::get_gravity() * player.movement_property("GravityScale");
+    let (radius, half_height) = player.capsule_size();
+    let mut points = vec![Vector { x, y, z }];
+    let mut time = 0.;
+    while time < duration {
+        let start = *points.last().unwrap();
+        let end = Vector {
+            x: start.x + vx * TRAJECTORY_STEP,
+            y: start.y + vy * TRAJECTORY_STEP,
+            z: start.z + vz * TRAJECTORY_STEP + gravity * TRAJECTORY_STEP * TRAJECTORY_STEP / 2.,
+        };
+        vz += gravity * TRAJECTORY_STEP;
+        let hit = KismetSystemLibrary::capsule_trace(
+            AMyCharacter::get_player(),
+            FVector { x: start.x, y: start.y, z: start.z },
+            FVector { x: end.x, y: end.y, z: end.z },
+            radius, half_height, 0,
+        );
+        if let Some(hit) = hit {
+            points.push(Vector { x: hit.location.x, y: hit.location.y, z: hit.location.z });
+            break;
+        }
+        points.push(end);
+        time += TRAJECTORY_STEP;
+    }
+    points
+}
+
+/// Sample the player's location into the trail and predict the trajectory once per tick instead of every frame
+pub(super) fn tick() {
+    let mut overlay = OVERLAY.lock().unwrap();
+    if let Some(trail) = overlay.trail.as_mut() {
+        let player = AMyCharacter::get_player();
+        let (x, y, z) = player.location();
+        let (vx, vy, _) = player.velocity();
+        trail.samples.push_back((Vector { x, y, z }, (vx * vx + vy * vy).sqrt()));
+        while trail.samples.len() > trail.settings.length as usize {
+            trail.samples.pop_front();
+        }
+    }
+    if let Some(trajectory) = overlay.trajectory.as_mut() {
+        trajectory.points = predict_trajectory(trajectory.settings.duration);
+    }
+}
+
+/// Whether the trail or trajectory prediction is enabled
+pub(super) fn is_active() -> bool {
+    let overlay = OVERLAY.lock().unwrap();
+    overlay.trail.is_some() || overlay.trajectory.is_some()
+}
+
+/// Add the screen-space lines of the trail and trajectory prediction, drawn by [`super::debug_draw::draw`]
+pub(super) fn lines(view: &View, lines: &mut Vec<HudLine>) {
+    let overlay = OVERLAY.lock().unwrap();
+    if let Some(trail) = &overlay.trail {
+        let TrailSettings { max_speed, thickness, .. } = trail.settings;
+        lines.extend(trail.samples.iter().zip(trail.samples.iter().skip(1))
+            .filter_map(|((start, speed), (end, _))| Some((view.project_line(*start, *end)?, *speed)))
+            .map(|((start, end), speed)| HudLine { start, end, color: speed_color(speed, max_speed), thickness }));
+    }
+    if let Some(Trajectory { settings, points }) = &overlay.trajectory {
+        let color = (settings.color.red, settings.color.green, settings.color.blue, settings.color.alpha);
+        lines.extend(points.iter().zip(points.iter().skip(1))
+            .filter_map(|(start, end)| view.project_line(*start, *end))
+            .map(|(start, end)| HudLine { start, end, color, thickness: settings.thickness }));
+    }
+}
+
+/// Disable the trail and trajectory prediction
+pub(super) fn disable() {
+    let mut overlay = OVERLAY.lock().unwrap();
+    overlay.trail = None;
+    overlay.trajectory = None;
+}
+
+/// Record the player's location every tick and draw it as trail coloured by horizontal speed
+#[rebo::function("Tas::trail_enable")]
+pub fn trail_enable(settings: TrailSettings) -> Result<(), TrailError> {
+    if settings.length == 0 {
+        return Err(TrailError::InvalidLength);
+    }
+    if settings.max_speed.is_nan() || settings.max_speed <= 0. {
+        return Err(TrailError::InvalidMaxSpeed);
+    }
+    let mut overlay = OVERLAY.lock().unwrap();
+    match overlay.trail.as_mut() {
+        Some(trail) => trail.settings = settings,
+        None => overlay.trail = Some(Trail { settings, samples: VecDeque::new() }),
+    }
+    Ok(())
+}
+#[rebo::function("Tas::trail_disable")]
+pub fn trail_disable() {
+    OVERLAY.lock().unwrap().trail = None;
+}
+#[rebo::function("Tas::trail_clear")]
+pub fn trail_clear() {
+    if let Some(trail) = OVERLAY.lock().unwrap().trail.as_mut() {
+        trail.samples.clear();
+    }
+}
+/// Draw the predicted arc of the player's current flight or of a jump from the ground, updated every tick
+#[rebo::function("Tas::trajectory_enable")]
+pub fn trajectory_enable(settings: TrajectorySettings) -> Result<(), TrailError> {
+    if !settings.duration.is_finite() || settings.duration <= 0. || settings.duration > MAX_TRAJECTORY_DURATION {
+        return Err(TrailError::InvalidDuration);
+    }
+    let mut overlay = OVERLAY.lock().unwrap();
+    match overlay.trajectory.as_mut() {
+        Some(trajectory) => trajectory.settings = settings,
+        None => overlay.trajectory = Some(Trajectory { settings, points: Vec::new() }),
+    }
+    Ok(())
+}
+#[rebo::function("Tas::trajectory_disable")]
+pub fn trajectory_disable() {
+    OVERLAY.lock().unwrap().trajectory = None;
+}
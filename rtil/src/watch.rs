@@ -37,12 +37,16 @@ impl<T> Sender<T> {
 }
 impl<T> Drop for Sender<T> {
     fn drop(&mut self) {
+        // hold the lock to not race with a receiver about to wait
+        let _lock = self.shared.element.lock().unwrap();
         self.shared.tx_closed.store(true, Ordering::Relaxed);
+        self.shared.condvar.notify_all();
     }
 }
 
 impl<T> Receiver<T> {
-    /// Consumes the element if there is one, otherwise waits for the next element
+    /// Consumes the element if there is one, otherwise waits for the next element.
+    /// Returns `Err` once the sender is dropped.
     pub fn read_consume(&self) -> Result<T, ()> {
         if self.shared.tx_closed.load(Ordering::Relaxed) {
             return Err(());
@@ -51,6 +55,8 @@ impl<T> Receiver<T> {
         loop {
             if let Some(element) = lock.take() {
                 return Ok(element);
+            } else if self.shared.tx_closed.load(Ordering::Relaxed) {
+                return Err(());
             } else {
                 lock = self.shared.condvar.wait(lock).unwrap();
             }
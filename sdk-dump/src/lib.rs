@@ -0,0 +1,286 @@
+//! Format of the SDK dump written by `Tas::dump_sdk` and diffing of two dumps.
+//!
+//! The dump contains every UClass, UScriptStruct and UFunction found in the GUObjectArray.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use serde::{Serialize, Deserialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SdkDump {
+    pub classes: Vec<StructDump>,
+    pub structs: Vec<StructDump>,
+    pub functions: Vec<FunctionDump>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StructDump {
+    /// path of the package containing this struct, e.g. `/Script/Engine`
+    #[serde(default)]
+    pub outer: String,
+    pub name: String,
+    /// direct super first, ending with the root (e.g. `Object`)
+    pub super_chain: Vec<String>,
+    pub size: usize,
+    pub min_alignment: usize,
+    /// only the properties declared by this struct itself, not the inherited ones
+    pub properties: Vec<PropertyDump>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertyDump {
+    pub name: String,
+    /// UE property class, e.g. `FloatProperty`
+    pub kind: String,
+    /// C++-like type, e.g. `TArray<BP_IslandChunk_C*>`
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub offset: isize,
+    pub size: usize,
+    pub array_dim: i32,
+    pub flags: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionDump {
+    /// path of the struct or class declaring this function, e.g. `/Script/Engine.Actor`
+    pub owner: String,
+    pub name: String,
+    pub flags: u32,
+    pub parms_size: u16,
+    pub return_value_offset: u16,
+    pub params: Vec<PropertyDump>,
+}
+
+impl StructDump {
+    /// unique path of the struct, same-named structs of different packages are different structs
+    pub fn full_name(&self) -> String {
+        if self.outer.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}.{}", self.outer, self.name)
+        }
+    }
+}
+
+impl FunctionDump {
+    pub fn full_name(&self) -> String {
+        format!("{}::{}", self.owner, self.name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ItemKind {
+    Class,
+    Struct,
+    Function,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// human-readable descriptions of what changed
+    Modified(Vec<String>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub kind: ItemKind,
+    pub name: String,
+    pub change: ChangeKind,
+}
+
+impl Display for ItemKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemKind::Class => write!(f, "class"),
+            ItemKind::Struct => write!(f, "struct"),
+            ItemKind::Function => write!(f, "function"),
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.change {
+            ChangeKind::Added => write!(f, "+ {} {}", self.kind, self.name),
+            ChangeKind::Removed => write!(f, "- {} {}", self.kind, self.name),
+            ChangeKind::Modified(details) => {
+                write!(f, "~ {} {}", self.kind, self.name)?;
+                for detail in details {
+                    write!(f, "\n    {detail}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Compare two dumps, returning all added, removed and modified classes, structs and functions.
+pub fn diff(old: &SdkDump, new: &SdkDump) -> Vec<Change> {
+    let mut changes = Vec::new();
+    diff_items(ItemKind::Class, &old.classes, &new.classes, StructDump::full_name, diff_struct, &mut changes);
+    diff_items(ItemKind::Struct, &old.structs, &new.structs, StructDump::full_name, diff_struct, &mut changes);
+    diff_items(ItemKind::Function, &old.functions, &new.functions, FunctionDump::full_name, diff_function, &mut changes);
+    changes
+}
+
+fn diff_items<T>(
+    kind: ItemKind, old: &[T], new: &[T], key: impl Fn(&T) -> String,
+    diff_item: impl Fn(&T, &T) -> Vec<String>, changes: &mut Vec<Change>,
+) {
+    let old: BTreeMap<_, _> = old.iter().map(|item| (key(item), item)).collect();
+    let new: BTreeMap<_, _> = new.iter().map(|item| (key(item), item)).collect();
+    for (name, old_item) in &old {
+        match new.get(name) {
+            None => changes.push(Change { kind, name: name.clone(), change: ChangeKind::Removed }),
+            Some(new_item) => {
+                let details = diff_item(old_item, new_item);
+                if !details.is_empty() {
+                    changes.push(Change { kind, name: name.clone(), change: ChangeKind::Modified(details) });
+                }
+            }
+        }
+    }
+    for name in new.keys().filter(|name| !old.contains_key(*name)) {
+        changes.push(Change { kind, name: name.clone(), change: ChangeKind::Added });
+    }
+}
+
+fn diff_struct(old: &StructDump, new: &StructDump) -> Vec<String> {
+    let mut details = Vec::new();
+    if old.super_chain != new.super_chain {
+        details.push(format!("super chain: {} -> {}", old.super_chain.join(" : "), new.super_chain.join(" : ")));
+    }
+    if old.size != new.size {
+        details.push(format!("size: {:#x} -> {:#x}", old.size, new.size));
+    }
+    if old.min_alignment != new.min_alignment {
+        details.push(format!("min alignment: {} -> {}", old.min_alignment, new.min_alignment));
+    }
+    details.extend(diff_properties("property", &old.properties, &new.properties));
+    details
+}
+
+fn diff_function(old: &FunctionDump, new: &FunctionDump) -> Vec<String> {
+    let mut details = Vec::new();
+    if old.flags != new.flags {
+        details.push(format!("flags: {:#x} -> {:#x}", old.flags, new.flags));
+    }
+    if old.parms_size != new.parms_size {
+        details.push(format!("parms size: {:#x} -> {:#x}", old.parms_size, new.parms_size));
+    }
+    if old.return_value_offset != new.return_value_offset {
+        details.push(format!("return value offset: {:#x} -> {:#x}", old.return_value_offset, new.return_value_offset));
+    }
+    details.extend(diff_properties("param", &old.params, &new.params));
+    details
+}
+
+fn diff_properties(what: &str, old: &[PropertyDump], new: &[PropertyDump]) -> Vec<String> {
+    let mut details = Vec::new();
+    for old_prop in old {
+        let Some(new_prop) = new.iter().find(|p| p.name == old_prop.name) else {
+            details.push(format!("- {what} {} {}", old_prop.type_name, old_prop.name));
+            continue;
+        };
+        if old_prop.kind != new_prop.kind || old_prop.type_name != new_prop.type_name {
+            details.push(format!("{what} {} type: {} -> {}", old_prop.name, old_prop.type_name, new_prop.type_name));
+        }
+        if old_prop.offset != new_prop.offset {
+            details.push(format!("{what} {} offset: {:#x} -> {:#x}", old_prop.name, old_prop.offset, new_prop.offset));
+        }
+        if old_prop.size != new_prop.size {
+            details.push(format!("{what} {} size: {:#x} -> {:#x}", old_prop.name, old_prop.size, new_prop.size));
+        }
+        if old_prop.array_dim != new_prop.array_dim {
+            details.push(format!("{what} {} array dim: {} -> {}", old_prop.name, old_prop.array_dim, new_prop.array_dim));
+        }
+        if old_prop.flags != new_prop.flags {
+            details.push(format!("{what} {} flags: {:#x} -> {:#x}", old_prop.name, old_prop.flags, new_prop.flags));
+        }
+    }
+    for new_prop in new.iter().filter(|p| !old.iter().any(|o| o.name == p.name)) {
+        details.push(format!("+ {what} {} {}", new_prop.type_name, new_prop.name));
+    }
+    details
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prop(name: &str, offset: isize) -> PropertyDump {
+        PropertyDump {
+            name: name.to_string(),
+            kind: "FloatProperty".to_string(),
+            type_name: "FloatProperty".to_string(),
+            offset,
+            size: 4,
+            array_dim: 1,
+            flags: 0,
+        }
+    }
+
+    fn class(name: &str, properties: Vec<PropertyDump>) -> StructDump {
+        StructDump {
+            outer: format!("/Game/{name}"),
+            name: name.to_string(),
+            super_chain: vec!["Actor".to_string(), "Object".to_string()],
+            size: 0x230,
+            min_alignment: 8,
+            properties,
+        }
+    }
+
+    #[test]
+    fn identical_dumps_have_no_changes() {
+        let dump = SdkDump { classes: vec![class("BP_Lift_C", vec![prop("Speed", 0x220)])], ..SdkDump::default() };
+        assert_eq!(diff(&dump, &dump.clone()), vec![]);
+    }
+
+    #[test]
+    fn added_removed_and_modified() {
+        let old = SdkDump {
+            classes: vec![
+                class("BP_Lift_C", vec![prop("Speed", 0x220), prop("Distance", 0x224)]),
+                class("BP_Removed_C", vec![]),
+            ],
+            ..SdkDump::default()
+        };
+        let new = SdkDump {
+            classes: vec![
+                class("BP_Lift_C", vec![prop("Speed", 0x228), prop("Delay", 0x22c)]),
+                class("BP_Added_C", vec![]),
+            ],
+            ..SdkDump::default()
+        };
+        let changes = diff(&old, &new);
+        assert_eq!(changes, vec![
+            Change { kind: ItemKind::Class, name: "/Game/BP_Lift_C.BP_Lift_C".to_string(), change: ChangeKind::Modified(vec![
+                "property Speed offset: 0x220 -> 0x228".to_string(),
+                "- property FloatProperty Distance".to_string(),
+                "+ property FloatProperty Delay".to_string(),
+            ])},
+            Change { kind: ItemKind::Class, name: "/Game/BP_Removed_C.BP_Removed_C".to_string(), change: ChangeKind::Removed },
+            Change { kind: ItemKind::Class, name: "/Game/BP_Added_C.BP_Added_C".to_string(), change: ChangeKind::Added },
+        ]);
+    }
+
+    #[test]
+    fn same_name_in_different_packages() {
+        let mut engine = class("Widget", vec![prop("Size", 0x30)]);
+        engine.outer = "/Script/Engine".to_string();
+        let mut umg = class("Widget", vec![prop("Size", 0x40)]);
+        umg.outer = "/Script/UMG".to_string();
+        let old = SdkDump { structs: vec![engine.clone(), umg.clone()], ..SdkDump::default() };
+        umg.properties[0].offset = 0x48;
+        let new = SdkDump { structs: vec![engine, umg], ..SdkDump::default() };
+        assert_eq!(diff(&old, &new), vec![
+            Change { kind: ItemKind::Struct, name: "/Script/UMG.Widget".to_string(), change: ChangeKind::Modified(vec![
+                "property Size offset: 0x40 -> 0x48".to_string(),
+            ])},
+        ]);
+    }
+}
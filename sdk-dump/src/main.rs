@@ -0,0 +1,26 @@
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::process;
+
+use sdk_dump::SdkDump;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let [old, new] = args.as_slice() else {
+        eprintln!("Usage: sdk-diff <old-dump.json> <new-dump.json>");
+        process::exit(2);
+    };
+    let old = read_dump(old);
+    let new = read_dump(new);
+    let changes = sdk_dump::diff(&old, &new);
+    for change in &changes {
+        println!("{change}");
+    }
+    println!("{} changes", changes.len());
+}
+
+fn read_dump(path: &str) -> SdkDump {
+    let file = File::open(path).unwrap_or_else(|e| panic!("Couldn't open dump {path}: {e}"));
+    serde_json::from_reader(BufReader::new(file)).unwrap_or_else(|e| panic!("Couldn't parse dump {path}: {e}"))
+}
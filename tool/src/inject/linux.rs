@@ -0,0 +1,273 @@
+use std::collections::HashSet;
+use std::env;
+use std::fs::{self, File};
+use std::io;
+use std::mem;
+use std::ptr;
+use std::ffi::{CString, c_void};
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use libc::{pid_t, user_regs_struct};
+
+const PROCESS_NAME: &str = "Refunct-Linux-Shipping";
+/// `call rax; int3`
+const CALL_RAX_INT3: [u8; 3] = [0xff, 0xd0, 0xcc];
+/// x86_64 SysV ABI red zone below the stack pointer, which we must not clobber
+const RED_ZONE: usize = 128;
+const AT_ENTRY: u64 = 9;
+/// Prefix of the unique filenames rtil is injected under, see `inject`
+const UNIQUE_PREFIX: &str = "librtil-";
+/// rtil can't be unloaded on Linux and stays mapped after an unload, so limit how many inert
+/// copies we leak into the game by reloading
+const MAX_UNLOADED_COPIES: usize = 1;
+
+// Attach to the running game with ptrace, make its main thread call
+// `dlopen(librtil.so)` and restore its previous state afterwards.
+// Our call-stub is written over the entry point of the executable, which is
+// only ever executed once on startup and thus safe to overwrite temporarily
+// while other threads keep running.
+pub fn inject() {
+    let rtil = env::var("RTIL").unwrap_or("./librtil.so".to_string());
+    let mut path = Path::new(&rtil);
+    if !path.is_file() {
+        path = Path::new("../rtil/target/release/librtil.so");
+    }
+    let absolute = path.canonicalize().unwrap();
+    // dlopen returns the already loaded library if the path is the same, which is the case
+    // after an unload, because glibc can't unload rtil - use a unique path instead
+    let unique = env::temp_dir().join(format!("{}{}.so", UNIQUE_PREFIX, SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis()));
+    fs::copy(&absolute, &unique).unwrap();
+    let absolute = unique;
+    println!("Trying to inject {}", absolute.display());
+    let pid = pidof().expect("Cannot find running Refunct-Linux-Shipping process");
+    println!("Found Refunct with pid {}", pid);
+    let res = inject_into(pid, &absolute);
+    // the library stays mapped after dlopen returned, so the copy isn't needed anymore
+    let _ = fs::remove_file(&absolute);
+    match res {
+        Ok(handle) => println!("dlopen returned {:#x}", handle),
+        Err(e) => panic!("Could not inject into Refunct: {} (is /proc/sys/kernel/yama/ptrace_scope set to 0?)", e),
+    }
+}
+
+/// Check if unloading rtil and injecting it again stays within `MAX_UNLOADED_COPIES`.
+pub fn can_reload() -> bool {
+    let Some(pid) = pidof() else { return true };
+    let Ok(maps) = fs::read_to_string(format!("/proc/{}/maps", pid)) else { return true };
+    // every copy of rtil currently mapped is inert after the unload
+    let copies: HashSet<&str> = maps.lines()
+        .filter_map(|line| line.split_whitespace().nth(5))
+        .filter(|path| Path::new(path).file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(UNIQUE_PREFIX)))
+        .collect();
+    copies.len() <= MAX_UNLOADED_COPIES
+}
+
+fn pidof() -> Option<pid_t> {
+    fs::read_dir("/proc").ok()?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+        .find(|pid: &pid_t| fs::read_link(format!("/proc/{}/exe", pid)).ok()
+            .is_some_and(|exe| exe.file_name().is_some_and(|name| name == PROCESS_NAME)))
+}
+
+/// Load the library at `lib` into the process `pid` via `dlopen`, returning the library handle.
+pub fn inject_into(pid: pid_t, lib: &Path) -> io::Result<usize> {
+    let dlopen = find_remote_dlopen(pid)?;
+    let entry = find_remote_entry(pid)?;
+    let lib = CString::new(lib.as_os_str().as_bytes())?;
+
+    let tracee = Tracee::seize(pid)?;
+    let handle = tracee.call(entry, dlopen, lib.as_bytes_with_nul(), (libc::RTLD_NOW | libc::RTLD_GLOBAL) as u64)?;
+    if handle == 0 {
+        return Err(io::Error::other("dlopen in target process returned NULL"));
+    }
+    Ok(handle)
+}
+
+/// Find the address of `dlopen` within the target's libc (glibc >= 2.34) or libdl (glibc < 2.34).
+fn find_remote_dlopen(pid: pid_t) -> io::Result<usize> {
+    let maps = fs::read_to_string(format!("/proc/{}/maps", pid))?;
+    let mut candidates: Vec<(usize, &str)> = maps.lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let range = parts.next()?;
+            let _perms = parts.next()?;
+            let offset = parts.next()?;
+            let path = parts.nth(2)?;
+            let file_name = Path::new(path).file_name()?.to_str()?;
+            let is_lib = file_name.starts_with("libc.so") || file_name.starts_with("libc-")
+                || file_name.starts_with("libdl.so") || file_name.starts_with("libdl-");
+            if !is_lib || usize::from_str_radix(offset, 16).ok()? != 0 {
+                return None;
+            }
+            let base = usize::from_str_radix(range.split_once('-')?.0, 16).ok()?;
+            Some((base, path))
+        }).collect();
+    // prefer libc over libdl
+    candidates.sort_by_key(|&(_, path)| path.contains("libdl"));
+    candidates.dedup();
+
+    for (base, path) in candidates {
+        // go through the target's root to handle processes in other mount namespaces (e.g. steam runtime)
+        let file = format!("/proc/{}/root{}", pid, path);
+        let dlopen = dynsym::iter(&file).into_iter()
+            .find(|(name, addr)| name == "dlopen" && *addr != 0);
+        if let Some((_, addr)) = dlopen {
+            return Ok(base + addr);
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::NotFound, "couldn't find dlopen in the target process"))
+}
+
+fn find_remote_entry(pid: pid_t) -> io::Result<usize> {
+    let auxv = fs::read(format!("/proc/{}/auxv", pid))?;
+    auxv.chunks_exact(16)
+        .map(|chunk| (u64::from_ne_bytes(chunk[..8].try_into().unwrap()), u64::from_ne_bytes(chunk[8..].try_into().unwrap())))
+        .find(|&(key, _)| key == AT_ENTRY)
+        .map(|(_, entry)| entry as usize)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no AT_ENTRY in auxv of the target process"))
+}
+
+/// A thread stopped via `PTRACE_SEIZE` + `PTRACE_INTERRUPT`, detached again on drop.
+struct Tracee {
+    pid: pid_t,
+    mem: File,
+}
+
+impl Tracee {
+    fn seize(pid: pid_t) -> io::Result<Tracee> {
+        ptrace(libc::PTRACE_SEIZE, pid, 0)?;
+        let tracee = Tracee { pid, mem: File::options().read(true).write(true).open(format!("/proc/{}/mem", pid))? };
+        ptrace(libc::PTRACE_INTERRUPT, pid, 0)?;
+        loop {
+            let status = tracee.wait()?;
+            if status >> 16 == libc::PTRACE_EVENT_STOP {
+                return Ok(tracee);
+            }
+            // signal-delivery-stop before our interrupt arrived: deliver the signal and wait again
+            ptrace(libc::PTRACE_CONT, pid, libc::WSTOPSIG(status) as usize)?;
+        }
+    }
+
+    fn wait(&self) -> io::Result<i32> {
+        let mut status = 0;
+        if unsafe { libc::waitpid(self.pid, &mut status, libc::__WALL) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if !libc::WIFSTOPPED(status) {
+            return Err(io::Error::other("target process exited while being traced"));
+        }
+        Ok(status)
+    }
+
+    fn get_regs(&self) -> io::Result<user_regs_struct> {
+        let mut regs: user_regs_struct = unsafe { mem::zeroed() };
+        ptrace(libc::PTRACE_GETREGS, self.pid, &mut regs as *mut _ as usize)?;
+        Ok(regs)
+    }
+
+    fn set_regs(&self, regs: &user_regs_struct) -> io::Result<()> {
+        ptrace(libc::PTRACE_SETREGS, self.pid, regs as *const _ as usize)
+    }
+
+    /// Call `function(arg, arg2)` in the tracee with `arg` being a pointer to a copy of `data`.
+    ///
+    /// The call-stub is written to `code_addr` and restored afterwards, as are all registers.
+    fn call(&self, code_addr: usize, function: usize, data: &[u8], arg2: u64) -> io::Result<usize> {
+        let saved_regs = self.get_regs()?;
+        let mut saved_code = [0u8; CALL_RAX_INT3.len()];
+        self.mem.read_exact_at(&mut saved_code, code_addr as u64)?;
+
+        let result = (|| {
+            let sp = (saved_regs.rsp as usize - RED_ZONE - data.len()) & !0xf;
+            self.mem.write_all_at(data, sp as u64)?;
+            self.mem.write_all_at(&CALL_RAX_INT3, code_addr as u64)?;
+
+            let mut regs = saved_regs;
+            regs.rip = code_addr as u64;
+            regs.rax = function as u64;
+            regs.rdi = sp as u64;
+            regs.rsi = arg2;
+            regs.rsp = sp as u64;
+            // don't let the kernel restart an interrupted syscall at our stub
+            regs.orig_rax = u64::MAX;
+            self.set_regs(&regs)?;
+
+            self.run_until_trap()?;
+            self.get_regs()
+        })();
+
+        // restore both even if one of them fails, the tracee can't continue with either one broken
+        let restored_code = self.mem.write_all_at(&saved_code, code_addr as u64);
+        let restored_regs = self.set_regs(&saved_regs);
+        let regs = result?;
+        restored_code?;
+        restored_regs?;
+        Ok(regs.rax as usize)
+    }
+
+    fn run_until_trap(&self) -> io::Result<()> {
+        let mut signal = 0;
+        loop {
+            ptrace(libc::PTRACE_CONT, self.pid, signal)?;
+            let status = self.wait()?;
+            match libc::WSTOPSIG(status) {
+                libc::SIGTRAP => return Ok(()),
+                // group-stops don't carry a signal to deliver
+                _ if status >> 16 == libc::PTRACE_EVENT_STOP => signal = 0,
+                sig => signal = sig as usize,
+            }
+        }
+    }
+}
+
+impl Drop for Tracee {
+    fn drop(&mut self) {
+        let _ = ptrace(libc::PTRACE_DETACH, self.pid, 0);
+    }
+}
+
+fn ptrace(request: libc::c_uint, pid: pid_t, data: usize) -> io::Result<()> {
+    let res = unsafe { libc::ptrace(request, pid, ptr::null_mut::<c_void>(), data as *mut c_void) };
+    if res < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn inject_into_dummy_process() {
+        let mut child = Command::new("sleep").arg("60")
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        // give the dynamic linker time to finish
+        thread::sleep(Duration::from_millis(200));
+        let pid = child.id() as i32;
+        let maps = || fs::read_to_string(format!("/proc/{}/maps", pid)).unwrap();
+        assert!(!maps().contains("libm.so"));
+
+        let result = super::inject_into(pid, Path::new("libm.so.6"));
+        let loaded = maps().contains("libm.so");
+        let alive = child.try_wait().unwrap().is_none();
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        let handle = result.unwrap();
+        assert_ne!(handle, 0);
+        assert!(loaded, "libm.so wasn't mapped into the target after injection");
+        assert!(alive, "target process died after injection");
+    }
+}
@@ -0,0 +1,5 @@
+#[cfg(windows)] mod windows;
+#[cfg(unix)] mod linux;
+
+#[cfg(windows)] pub use self::windows::{inject, can_reload};
+#[cfg(unix)] pub use self::linux::{inject, can_reload};
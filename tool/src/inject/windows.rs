@@ -10,6 +10,11 @@ use winapi::um::memoryapi::{VirtualAllocEx, WriteProcessMemory};
 use winapi::um::handleapi::CloseHandle;
 use winapi::um::winnt::{PROCESS_ALL_ACCESS, MEM_RESERVE, MEM_COMMIT, PAGE_READWRITE};
 
+/// rtil.dll is freed on unload, so it can be reloaded any number of times.
+pub fn can_reload() -> bool {
+    true
+}
+
 // http://resources.infosecinstitute.com/using-createremotethread-for-dll-injection-on-windows/
 pub fn inject() {
     unsafe {
@@ -7,48 +7,64 @@ compile_error!("must be compiled as 64bit on macOS (e.g. with `--target x86_64-a
 
 mod error;
 mod tas;
-#[cfg(windows)] mod inject;
+mod inject;
+mod map_convert;
 
 use std::env;
 use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::tas::Tas;
 
 fn main() {
-    let mut tas;
-    #[cfg(windows)]
-    {
-        // inject dll
-        println!("Testing if DLL is already injected");
-        match Tas::new() {
-            Ok(val) => {
-                tas = val;
-                println!("DLL already injected.");
-            },
-            Err(error::Error::CantConnectToRtil) => {
-                println!("DLL has not been injected yet, injecting...");
-                inject::inject();
-                println!("DLL Injected");
-                println!("Create tas...");
-                tas = Tas::new().unwrap();
-                println!("TAS created successfully.");
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    // map conversion and merging works offline without the game
+    match args.first().map(String::as_str) {
+        Some("map-export") => return map_convert::export(&args[1..]),
+        Some("map-import") => return map_convert::import(&args[1..]),
+        Some("map-diff") => return map_convert::diff(&args[1..]),
+        Some("map-merge") => return map_convert::merge(&args[1..]),
+        _ => (),
+    }
+    if args.first().map(String::as_str) == Some("--reload") {
+        args.remove(0);
+        if let Ok(tas) = Tas::new() {
+            if !inject::can_reload() {
+                panic!("rtil was already reloaded in this game; restart Refunct to load a new build");
             }
+            println!("Unloading rtil...");
+            tas.unload();
         }
     }
-    #[cfg(unix)]
-    {
-        println!("Create tas...");
-        tas = Tas::new().unwrap();
-        println!("TAS created successfully.");
-    }
-    let script_file = if env::args().len() == 1 {
-        if !Path::new("main.re").is_file() {
-            panic!("No tas file specified. Usage: refunct-tas <file.re>");
-        } else {
-            PathBuf::from("main.re")
+
+    // inject library
+    println!("Testing if rtil is already injected");
+    let mut tas = match Tas::new() {
+        Ok(tas) => {
+            println!("rtil already injected.");
+            tas
+        },
+        Err(error::Error::CantConnectToRtil) => {
+            println!("rtil has not been injected yet, injecting...");
+            inject::inject();
+            println!("rtil injected");
+            println!("Create tas...");
+            let tas = connect_after_injection();
+            println!("TAS created successfully.");
+            tas
         }
-    } else {
-        PathBuf::from(env::args().nth(1).unwrap())
+    };
+    if args.first().map(String::as_str) == Some("dump-sdk") {
+        let path = args.get(1).map(String::as_str).unwrap_or("sdk.json");
+        println!("Dumping SDK to {} ...", path);
+        tas.dump_sdk(path);
+        return;
+    }
+    let script_file = match args.first() {
+        Some(file) => PathBuf::from(file),
+        None if Path::new("main.re").is_file() => PathBuf::from("main.re"),
+        None => panic!("No tas file specified. Usage: refunct-tas [--reload] <file.re> | refunct-tas dump-sdk [<out.json>] | refunct-tas map-export|map-import|map-diff|map-merge ..."),
     };
     println!("Executing Script {} ...", script_file.display());
     tas.execute(script_file);
@@ -56,3 +72,16 @@ fn main() {
     println!("Finished");
 }
 
+/// rtil needs some time after injection until its listener is started
+fn connect_after_injection() -> Tas {
+    let start = Instant::now();
+    loop {
+        match Tas::new() {
+            Ok(tas) => return tas,
+            Err(error::Error::CantConnectToRtil) if start.elapsed() < Duration::from_secs(60) => {
+                thread::sleep(Duration::from_millis(500))
+            }
+            Err(e) => panic!("{}", e),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Conversion of map files from the maps folder to OBJ / glTF models and back,
+//! and comparing and merging of map files.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::process;
+
+use refunct_map::RefunctMap;
+
+enum ModelFormat {
+    Obj,
+    Gltf,
+}
+
+impl ModelFormat {
+    fn from_path(path: &str) -> ModelFormat {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("obj") => ModelFormat::Obj,
+            Some(ext) if ext.eq_ignore_ascii_case("gltf") => ModelFormat::Gltf,
+            _ => {
+                eprintln!("Unknown model format of {path}, expected a .obj or .gltf file");
+                process::exit(2);
+            }
+        }
+    }
+}
+
+/// `refunct-tas map-export <map.json> <out.obj|out.gltf>`
+pub fn export(args: &[String]) {
+    let [map, out] = args else {
+        eprintln!("Usage: refunct-tas map-export <map.json> <out.obj|out.gltf>");
+        process::exit(2);
+    };
+    let map = read_map(map);
+    let model = match ModelFormat::from_path(out) {
+        ModelFormat::Obj => refunct_map::obj::export(&map),
+        ModelFormat::Gltf => refunct_map::gltf::export(&map),
+    };
+    std::fs::write(out, model).unwrap_or_else(|e| panic!("Couldn't write {out}: {e}"));
+    println!("Exported {} elements to {out}", map.elements().count());
+}
+
+/// `refunct-tas map-import <model.obj|model.gltf> <base-map.json> <out.json>`
+///
+/// Everything except for the element transforms is taken from the base map.
+pub fn import(args: &[String]) {
+    let [model_path, base, out] = args else {
+        eprintln!("Usage: refunct-tas map-import <model.obj|model.gltf> <base-map.json> <out.json>");
+        process::exit(2);
+    };
+    let base = read_map(base);
+    let model = std::fs::read_to_string(model_path).unwrap_or_else(|e| panic!("Couldn't read {model_path}: {e}"));
+    let transforms = match ModelFormat::from_path(model_path) {
+        ModelFormat::Obj => refunct_map::obj::import(&model),
+        ModelFormat::Gltf => refunct_map::gltf::import(&model),
+    };
+    let transforms = transforms.unwrap_or_else(|e| panic!("Couldn't import {model_path}: {e}"));
+    let count = transforms.len();
+    let map = base.with_transforms(transforms).unwrap_or_else(|e| panic!("Couldn't import {model_path}: {e}"));
+    let file = File::create(out).unwrap_or_else(|e| panic!("Couldn't create {out}: {e}"));
+    refunct_map::write_map(file, &map).unwrap_or_else(|e| panic!("Couldn't write {out}: {e}"));
+    println!("Imported {count} element transforms into {out}");
+}
+
+/// `refunct-tas map-diff <old.json> <new.json>`
+pub fn diff(args: &[String]) {
+    let [old, new] = args else {
+        eprintln!("Usage: refunct-tas map-diff <old.json> <new.json>");
+        process::exit(2);
+    };
+    for change in refunct_map::diff::diff(&read_map(old), &read_map(new)) {
+        println!("{change}");
+    }
+}
+
+/// `refunct-tas map-merge <base.json> <ours.json> <theirs.json> <out.json>`
+///
+/// The base is usually the original map saved with `Tas::save_map("original", Tas::original_map())`.
+/// Conflicting properties keep our value, are printed and make the command exit with 1.
+pub fn merge(args: &[String]) {
+    let [base, ours, theirs, out] = args else {
+        eprintln!("Usage: refunct-tas map-merge <base.json> <ours.json> <theirs.json> <out.json>");
+        process::exit(2);
+    };
+    let merged = refunct_map::diff::merge(&read_map(base), &read_map(ours), &read_map(theirs))
+        .unwrap_or_else(|e| panic!("Couldn't merge maps: {e}"));
+    let file = File::create(out).unwrap_or_else(|e| panic!("Couldn't create {out}: {e}"));
+    refunct_map::write_map(file, &merged.map).unwrap_or_else(|e| panic!("Couldn't write {out}: {e}"));
+    for conflict in &merged.conflicts {
+        println!("{conflict}");
+    }
+    if !merged.conflicts.is_empty() {
+        println!("Wrote {out} with {} conflicts, which kept the value of {ours}", merged.conflicts.len());
+        process::exit(1);
+    }
+    println!("Merged into {out} without conflicts");
+}
+
+fn read_map(path: &str) -> RefunctMap {
+    let file = File::open(path).unwrap_or_else(|e| panic!("Couldn't open map {path}: {e}"));
+    refunct_map::read_map(BufReader::new(file)).unwrap_or_else(|e| panic!("Couldn't read map {path}: {e}"))
+}
@@ -22,9 +22,15 @@ impl Tas {
         })
     }
 
+    /// Unload rtil from the game, returning once it confirmed that all hooks are removed.
+    pub fn unload(mut self) {
+        self.con.write_u8(2).unwrap();
+        self.wait_for(2);
+    }
+
     pub fn execute<P: AsRef<Path>>(&mut self, path: P) {
         let path = path.as_ref();
-        let mut file = File::open(path).expect(&format!("Couldn't open TAS file {:?}", path));
+        let mut file = File::open(path).unwrap_or_else(|_| panic!("Couldn't open TAS file {:?}", path));
         let mut code = String::new();
         file.read_to_string(&mut code).unwrap();
 
@@ -48,7 +54,26 @@ impl Tas {
         self.con.write_u32::<LittleEndian>(code.len() as u32).unwrap();
         self.con.write_all(code.as_bytes()).unwrap();
         println!("Tas Execution started");
+        self.wait_until_finished();
+    }
+
+    /// Dump all classes, structs and functions of the game to the JSON file at `path`.
+    pub fn dump_sdk<P: AsRef<Path>>(&mut self, path: P) {
+        let path = env::current_dir().unwrap().join(path);
+        let path = path.to_str().unwrap();
+        self.con.write_u8(4).unwrap();
+        self.con.write_u32::<LittleEndian>(path.len() as u32).unwrap();
+        self.con.write_all(path.as_bytes()).unwrap();
+        self.wait_until_finished();
+    }
 
+    /// Print everything rtil sends until it reports that it's finished.
+    fn wait_until_finished(&mut self) {
+        self.wait_for(1);
+    }
+
+    /// Print everything rtil sends until it sends the `response` command.
+    fn wait_for(&mut self, response: u8) {
         loop {
             match self.con.read_u8().unwrap() {
                 0 => {
@@ -60,7 +85,15 @@ impl Tas {
                 }
                 1 => {
                     println!("Execution Finished");
-                    break;
+                    if response == 1 {
+                        break;
+                    }
+                }
+                2 => {
+                    println!("rtil unloaded");
+                    if response == 2 {
+                        break;
+                    }
                 }
                 255 => match self.con.read_u8().unwrap() {
                     0 => println!("Error: Unknown Command."),